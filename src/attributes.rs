@@ -11,9 +11,31 @@ use std::marker::PhantomData;
 use std::cmp::max;
 
 pub trait AttributeTrait {
-    fn declare(index: u32, offset: usize, stride: i32);
+    /// Declare this attribute and the rest of the chain, returning the next unused location.
+    fn declare(index: u32, offset: usize, stride: i32) -> u32;
     fn stride(total: i32, max_alignment: i32) -> i32;
 
+    /// Push the byte offset of this attribute and the rest of the chain onto `out`.
+    fn offsets(offset: usize, out: &mut Vec<usize>);
+
+    /// The number of attribute locations used by this attribute and the rest of the chain.
+    fn count() -> u32;
+
+    /// Like `declare`, but uses direct state access to set up formats on `vao`, sourcing every
+    /// attribute from the buffer `binding`.
+    fn declare_dsa(vao: GLuint, binding: u32, index: u32, offset: usize) -> u32;
+
+    /// Like `declare_dsa`, but sets up the formats on the currently bound VAO.
+    fn declare_format(binding: u32, index: u32, offset: usize) -> u32;
+
+    /// Push a description of each location of this attribute and the rest of the chain onto
+    /// `out`, without making any GL calls.
+    fn describe(offset: usize, stride: i32, out: &mut Vec<AttributeDesc>);
+
+    /// Declare this attribute and the rest of the chain each from its own buffer, taking the
+    /// `(buffer, location)` of each from the front of `sources`.
+    fn declare_separate(sources: &[(GLuint, u32)]);
+
     /// Calculate the padding necessary from offset to reach this Attributes alignment
     /// requirements.
     fn padding(offset: usize) -> usize;
@@ -23,7 +45,33 @@ pub struct AttributeTail;
 
 impl AttributeTrait for AttributeTail {
     #[inline]
-    fn declare(_: u32, _: usize, _: i32) {}
+    fn declare(index: u32, _: usize, _: i32) -> u32 {
+        index
+    }
+
+    #[inline]
+    fn offsets(_: usize, _: &mut Vec<usize>) {}
+
+    #[inline]
+    fn count() -> u32 {
+        0
+    }
+
+    #[inline]
+    fn declare_dsa(_: GLuint, _: u32, index: u32, _: usize) -> u32 {
+        index
+    }
+
+    #[inline]
+    fn declare_format(_: u32, index: u32, _: usize) -> u32 {
+        index
+    }
+
+    #[inline]
+    fn declare_separate(_: &[(GLuint, u32)]) {}
+
+    #[inline]
+    fn describe(_: usize, _: i32, _: &mut Vec<AttributeDesc>) {}
 
     #[inline]
     fn padding(_: usize) -> usize {
@@ -41,42 +89,357 @@ pub struct Attribute<T: ToGlAttrib, A: AttributeTrait>(PhantomData<(T, A)>);
 
 impl<T: ToGlAttrib, A: AttributeTrait> Attribute<T, A> {
     /// Shadow the trait method so the user never has to interact with the Traits
+    ///
+    /// Returns the first location after this layout, so that another layout can be declared
+    /// right after it.
+    #[inline]
+    pub fn declare(index: u32) -> u32 {
+        declare_layout::<Self>(index)
+    }
+
+    /// Like `declare`, but with an explicit `stride` in bytes, for vertices that carry data the
+    /// layout doesn't describe (e.g. trailing fields the shader ignores).
+    ///
+    /// Offsets are computed as usual.
     #[inline]
-    pub fn declare(index: u32) {
-        <Self as AttributeTrait>::declare(index, 0, Self::stride());
+    pub fn declare_with_stride(first_location: u32, stride: i32) -> u32 {
+        debug_check_locations(first_location + Self::count());
+        <Self as AttributeTrait>::declare(first_location, 0, stride)
     }
 
     #[inline]
     pub fn stride() -> i32 {
         <Self as AttributeTrait>::stride(0, T::alignment() as i32)
     }
+
+    /// Check, in debug builds, that this layout's stride equals the size of the vertex type `V`.
+    ///
+    /// This catches a layout that wasn't updated after the fields of `V` were changed.
+    ///
+    /// # Panics
+    ///
+    /// With debug assertions enabled, if the stride and `size_of::<V>()` differ.
+    #[inline]
+    pub fn assert_matches<V>() {
+        debug_assert!(Self::stride() as usize == ::std::mem::size_of::<V>(),
+                      "layout stride is {} bytes but size_of::<{}>() is {}", Self::stride(),
+                      ::std::any::type_name::<V>(), ::std::mem::size_of::<V>());
+    }
+
+    /// The byte offset of each attribute, as `declare` would pass to `glVertexAttribPointer`.
+    ///
+    /// Matrix attributes report the offset of their first column. No GL calls are made.
+    pub fn offsets() -> Vec<usize> {
+        let mut offsets = Vec::new();
+        <Self as AttributeTrait>::offsets(0, &mut offsets);
+        offsets
+    }
+
+    /// The number of attribute locations this layout occupies, counting each matrix column.
+    #[inline]
+    pub fn count() -> u32 {
+        <Self as AttributeTrait>::count()
+    }
+
+    /// Disable every attribute array that `declare(first_location)` enabled, returning the next
+    /// location after this layout.
+    #[inline]
+    pub fn disable(first_location: u32) -> u32 {
+        let end = first_location + Self::count();
+        for location in first_location..end {
+            unsafe {
+                gl::DisableVertexAttribArray(location);
+            }
+        }
+        end
+    }
+
+    /// Describe the `glVertexAttrib*Pointer` call `declare` makes for each location, without
+    /// making any GL calls.
+    ///
+    /// Matrix attributes produce one description per column. The result matches
+    /// `Layout::new(...).attributes()` for the equivalent `AttributeDesc`s.
+    pub fn describe() -> Vec<AttributeDesc> {
+        let mut out = Vec::new();
+        <Self as AttributeTrait>::describe(0, Self::stride(), &mut out);
+        out
+    }
+
+    /// Declare each attribute from its own tightly packed buffer, for non-interleaved vertex
+    /// data. `sources` holds the `(buffer, location)` of each attribute, in order.
+    ///
+    /// Each buffer is bound to `gl::ARRAY_BUFFER` in turn, and the last one is left bound.
+    ///
+    /// # Panics
+    ///
+    /// If `sources` does not have exactly one entry per attribute.
+    pub fn declare_separate(sources: &[(GLuint, u32)]) {
+        let attributes = Self::offsets().len();
+        assert!(sources.len() == attributes,
+                "expected a buffer and location for each of {} attributes, got {}", attributes,
+                sources.len());
+        <Self as AttributeTrait>::declare_separate(sources)
+    }
+
+    /// Declare this layout on `vao` with direct state access (OpenGL 4.5), without binding it.
+    ///
+    /// Every attribute is assigned to the buffer binding point `binding_index`; attach a buffer
+    /// with `glVertexArrayVertexBuffer(vao, binding_index, vbo, 0, Self::stride())`. Since GL
+    /// tracks divisors per binding rather than per attribute, the attributes must be either all
+    /// `Instanced` with the same divisor or not instanced at all; debug builds panic otherwise.
+    /// Returns the next unused location.
+    #[inline]
+    pub fn declare_dsa(vao: GLuint, binding_index: u32, first_location: u32) -> u32 {
+        debug_check_locations(first_location + Self::count());
+        debug_check_one_divisor::<Self>();
+        <Self as AttributeTrait>::declare_dsa(vao, binding_index, first_location, 0)
+    }
+
+    /// Declare only the formats of this layout on the bound VAO (OpenGL 4.3), with offsets
+    /// relative to the buffer binding point `binding_index`.
+    ///
+    /// Switching the buffer that feeds these attributes is then a single
+    /// `glBindVertexBuffer(binding_index, vbo, 0, Self::stride())` call. As with `declare_dsa`,
+    /// all attributes must share one divisor. Returns the next unused location.
+    #[inline]
+    pub fn declare_format(binding_index: u32, first_location: u32) -> u32 {
+        debug_check_locations(first_location + Self::count());
+        debug_check_one_divisor::<Self>();
+        <Self as AttributeTrait>::declare_format(binding_index, first_location, 0)
+    }
 }
 
 impl<T: ToGlAttrib, A: AttributeTrait> AttributeTrait for Attribute<T, A> {
     #[inline]
-    fn declare(index: u32, mut offset: usize, stride: i32) {
+    fn declare(index: u32, mut offset: usize, stride: i32) -> u32 {
         offset += Self::padding(offset);
-        unsafe {
-            gl::EnableVertexAttribArray(index);
-            gl::VertexAttribPointer(index, T::components(), T::gl_enum(), T::normalized(),
-                                    stride, offset as *const _);
+        // matrices occupy one location per column
+        let column_size = T::byte_size();
+        for column in 0..T::locations() {
+            let location = index + column;
+            unsafe {
+                gl::EnableVertexAttribArray(location);
+                attrib_pointer::<T>(location, stride, offset + column as usize * column_size);
+                if T::divisor() != 0 {
+                    gl::VertexAttribDivisor(location, T::divisor());
+                }
+            }
         }
-        A::declare(index + 1, offset + T::size() * T::components() as usize, stride);
+        A::declare(index + T::locations(), offset + attrib_size::<T>(), stride)
     }
 
     #[inline]
     fn stride(mut total: i32, max_alignment: i32) -> i32 {
         total += Self::padding(total as usize) as i32;
-        let size = T::size() as i32 * T::components();
+        let size = attrib_size::<T>() as i32;
         A::stride(total + size, max(T::alignment() as i32, max_alignment))
     }
 
+    #[inline]
+    fn offsets(mut offset: usize, out: &mut Vec<usize>) {
+        offset += Self::padding(offset);
+        out.push(offset);
+        A::offsets(offset + attrib_size::<T>(), out);
+    }
+
+    #[inline]
+    fn count() -> u32 {
+        T::locations() + A::count()
+    }
+
+    #[inline]
+    fn declare_dsa(vao: GLuint, binding: u32, index: u32, mut offset: usize) -> u32 {
+        offset += Self::padding(offset);
+        let column_size = T::byte_size();
+        for column in 0..T::locations() {
+            let location = index + column;
+            unsafe {
+                gl::EnableVertexArrayAttrib(vao, location);
+                vertex_array_attrib_format::<T>(vao, location,
+                                                offset + column as usize * column_size);
+                gl::VertexArrayAttribBinding(vao, location, binding);
+            }
+        }
+        if T::divisor() != 0 {
+            unsafe {
+                gl::VertexArrayBindingDivisor(vao, binding, T::divisor());
+            }
+        }
+        A::declare_dsa(vao, binding, index + T::locations(), offset + attrib_size::<T>())
+    }
+
+    #[inline]
+    fn declare_format(binding: u32, index: u32, mut offset: usize) -> u32 {
+        offset += Self::padding(offset);
+        let column_size = T::byte_size();
+        for column in 0..T::locations() {
+            let location = index + column;
+            unsafe {
+                gl::EnableVertexAttribArray(location);
+                attrib_format::<T>(location, offset + column as usize * column_size);
+                gl::VertexAttribBinding(location, binding);
+            }
+        }
+        if T::divisor() != 0 {
+            unsafe {
+                gl::VertexBindingDivisor(binding, T::divisor());
+            }
+        }
+        A::declare_format(binding, index + T::locations(), offset + attrib_size::<T>())
+    }
+
+    fn describe(mut offset: usize, stride: i32, out: &mut Vec<AttributeDesc>) {
+        offset += Self::padding(offset);
+        let column_size = T::byte_size();
+        for column in 0..T::locations() {
+            out.push(AttributeDesc {
+                components: T::gl_size(),
+                gl_enum: T::gl_enum(),
+                normalized: T::normalized() != gl::FALSE,
+                pointer_kind: T::pointer_kind(),
+                divisor: T::divisor(),
+                offset: offset + column as usize * column_size,
+                stride,
+            });
+        }
+        A::describe(offset + attrib_size::<T>(), stride, out);
+    }
+
+    #[inline]
+    fn declare_separate(sources: &[(GLuint, u32)]) {
+        let (buffer, index) = sources[0];
+        // locations needn't be consecutive here, so each attribute is checked on its own
+        debug_check_locations(index + T::locations());
+        let column_size = T::byte_size();
+        let stride = attrib_size::<T>() as i32;
+        unsafe {
+            gl::BindBuffer(gl::ARRAY_BUFFER, buffer);
+        }
+        for column in 0..T::locations() {
+            let location = index + column;
+            unsafe {
+                gl::EnableVertexAttribArray(location);
+                attrib_pointer::<T>(location, stride, column as usize * column_size);
+                if T::divisor() != 0 {
+                    gl::VertexAttribDivisor(location, T::divisor());
+                }
+            }
+        }
+        A::declare_separate(&sources[1..]);
+    }
+
     #[inline]
     fn padding(offset: usize) -> usize {
         (T::alignment() - offset % T::alignment()) % T::alignment()
     }
 }
 
+/// `Attribute::declare` for a layout only known through `AttributeTrait`, as in
+/// `raw::create_vao_with_layout`.
+#[inline]
+pub(crate) fn declare_layout<F: AttributeTrait>(first_location: u32) -> u32 {
+    debug_check_locations(first_location + F::count());
+    // the chain takes the largest alignment into account as it goes
+    F::declare(first_location, 0, F::stride(0, 1))
+}
+
+/// In debug builds, check that every attribute of `F` has the same divisor, since the
+/// separate-format calls can only set one per buffer binding.
+#[inline]
+fn debug_check_one_divisor<F: AttributeTrait>() {
+    if cfg!(debug_assertions) {
+        let mut attributes = Vec::new();
+        F::describe(0, 0, &mut attributes);
+        if let Some(first) = attributes.first() {
+            assert!(attributes.iter().all(|a| a.divisor == first.divisor),
+                    "attributes sharing a buffer binding must all have the same divisor");
+        }
+    }
+}
+
+/// In debug builds, check that locations below `end` exist, since GL ignores declarations at
+/// locations past `GL_MAX_VERTEX_ATTRIBS` apart from raising an error.
+#[inline]
+fn debug_check_locations(end: u32) {
+    if cfg!(debug_assertions) {
+        let max = ::raw::max_vertex_attribs();
+        assert!(end <= max, "layout needs attribute locations up to {} but the context only has {}",
+                end, max);
+    }
+}
+
+/// The number of bytes an attribute occupies in the buffer, across all of its columns.
+#[inline]
+fn attrib_size<T: ToGlAttrib>() -> usize {
+    T::byte_size() * T::locations() as usize
+}
+
+/// Issue the `glVertexAttrib*Pointer` call matching the attribute's `PointerKind`.
+#[inline]
+unsafe fn attrib_pointer<T: ToGlAttrib>(index: u32, stride: i32, offset: usize) {
+    match T::pointer_kind() {
+        PointerKind::Float => {
+            gl::VertexAttribPointer(index, T::gl_size(), T::gl_enum(), T::normalized(),
+                                    stride, offset as *const _);
+        },
+        PointerKind::Integer => {
+            gl::VertexAttribIPointer(index, T::gl_size(), T::gl_enum(), stride,
+                                     offset as *const _);
+        },
+        PointerKind::Long => {
+            gl::VertexAttribLPointer(index, T::gl_size(), T::gl_enum(), stride,
+                                     offset as *const _);
+        },
+    }
+}
+
+/// Issue the `glVertexAttrib*Format` call matching the attribute's `PointerKind`.
+#[inline]
+unsafe fn attrib_format<T: ToGlAttrib>(index: u32, offset: usize) {
+    match T::pointer_kind() {
+        PointerKind::Float => {
+            gl::VertexAttribFormat(index, T::gl_size(), T::gl_enum(), T::normalized(),
+                                   offset as GLuint);
+        },
+        PointerKind::Integer => {
+            gl::VertexAttribIFormat(index, T::gl_size(), T::gl_enum(), offset as GLuint);
+        },
+        PointerKind::Long => {
+            gl::VertexAttribLFormat(index, T::gl_size(), T::gl_enum(), offset as GLuint);
+        },
+    }
+}
+
+/// Issue the `glVertexArrayAttrib*Format` call matching the attribute's `PointerKind`.
+#[inline]
+unsafe fn vertex_array_attrib_format<T: ToGlAttrib>(vao: GLuint, index: u32, offset: usize) {
+    match T::pointer_kind() {
+        PointerKind::Float => {
+            gl::VertexArrayAttribFormat(vao, index, T::gl_size(), T::gl_enum(),
+                                        T::normalized(), offset as GLuint);
+        },
+        PointerKind::Integer => {
+            gl::VertexArrayAttribIFormat(vao, index, T::gl_size(), T::gl_enum(),
+                                         offset as GLuint);
+        },
+        PointerKind::Long => {
+            gl::VertexArrayAttribLFormat(vao, index, T::gl_size(), T::gl_enum(),
+                                         offset as GLuint);
+        },
+    }
+}
+
+/// Selects the `glVertexAttrib*Pointer` function used to declare an attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointerKind {
+    /// `glVertexAttribPointer`: values are converted to floats (e.g. `vec4` inputs).
+    Float,
+    /// `glVertexAttribIPointer`: values stay integers (e.g. `ivec4` or `uint` inputs).
+    Integer,
+    /// `glVertexAttribLPointer`: values stay 64-bit doubles (e.g. `dvec3` inputs).
+    Long,
+}
+
 pub trait ToGlAttrib {
     fn size() -> usize;
     #[inline]
@@ -86,14 +449,284 @@ pub trait ToGlAttrib {
     fn normalized() -> GLboolean;
     fn components() -> i32;
     fn gl_enum() -> GLenum;
+    /// The `size` argument to `glVertexAttribPointer`, normally the number of components.
+    #[inline]
+    fn gl_size() -> GLint {
+        Self::components()
+    }
+    /// The number of bytes a single location of this attribute occupies.
+    #[inline]
+    fn byte_size() -> usize {
+        Self::size() * Self::components() as usize
+    }
+    #[inline]
+    fn pointer_kind() -> PointerKind {
+        PointerKind::Float
+    }
+    /// The `glVertexAttribDivisor` of this attribute; 0 means it advances once per vertex.
+    #[inline]
+    fn divisor() -> u32 {
+        0
+    }
+    /// The number of consecutive locations (matrix columns) this attribute occupies.
+    #[inline]
+    fn locations() -> u32 {
+        1
+    }
 }
 
 /// Marker type for normalized attributes
 pub struct Normalized<T>(T);
 
+/// The bits of an IEEE 754 half precision float, declared as `GL_HALF_FLOAT`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Half(pub u16);
+
+/// Marker type for integer attributes, which are declared with `glVertexAttribIPointer`
+pub struct Integer<T>(T);
+
+/// Marker type for double precision attributes, which are declared with `glVertexAttribLPointer`
+pub struct Long<T>(T);
+
+/// Wrapper for per-instance attributes, which advance once every `DIVISOR` instances.
+///
+/// `Instanced<[f32; 4]>` uses a divisor of 1; `Instanced<[f32; 4], 2>` advances every other
+/// instance.
+pub struct Instanced<T, const DIVISOR: u32 = 1>(T);
+
+impl<T: ToGlAttrib, const DIVISOR: u32> ToGlAttrib for Instanced<T, DIVISOR> {
+    #[inline]
+    fn size() -> usize {
+        T::size()
+    }
+
+    #[inline]
+    fn alignment() -> usize {
+        T::alignment()
+    }
+
+    #[inline]
+    fn normalized() -> GLboolean {
+        T::normalized()
+    }
+
+    #[inline]
+    fn components() -> i32 {
+        T::components()
+    }
+
+    #[inline]
+    fn gl_enum() -> GLenum {
+        T::gl_enum()
+    }
+
+    #[inline]
+    fn gl_size() -> GLint {
+        T::gl_size()
+    }
+
+    #[inline]
+    fn byte_size() -> usize {
+        T::byte_size()
+    }
+
+    #[inline]
+    fn pointer_kind() -> PointerKind {
+        T::pointer_kind()
+    }
+
+    #[inline]
+    fn divisor() -> u32 {
+        DIVISOR
+    }
+
+    #[inline]
+    fn locations() -> u32 {
+        T::locations()
+    }
+}
+
+/// Wrapper that removes an attribute's alignment requirement, so it is placed directly after the
+/// previous attribute with no padding.
+///
+/// A layout made entirely of `Packed` attributes matches a `#[repr(C, packed)]` vertex struct.
+/// Not to be confused with `Packed2_10_10_10`, which packs components into a single integer.
+pub struct Packed<T>(T);
+
+impl<T: ToGlAttrib> ToGlAttrib for Packed<T> {
+    #[inline]
+    fn size() -> usize {
+        T::size()
+    }
+
+    #[inline]
+    fn alignment() -> usize {
+        1
+    }
+
+    #[inline]
+    fn normalized() -> GLboolean {
+        T::normalized()
+    }
+
+    #[inline]
+    fn components() -> i32 {
+        T::components()
+    }
+
+    #[inline]
+    fn gl_enum() -> GLenum {
+        T::gl_enum()
+    }
+
+    #[inline]
+    fn gl_size() -> GLint {
+        T::gl_size()
+    }
+
+    #[inline]
+    fn byte_size() -> usize {
+        T::byte_size()
+    }
+
+    #[inline]
+    fn pointer_kind() -> PointerKind {
+        T::pointer_kind()
+    }
+
+    #[inline]
+    fn divisor() -> u32 {
+        T::divisor()
+    }
+
+    #[inline]
+    fn locations() -> u32 {
+        T::locations()
+    }
+}
+
+/// A column major `mat3` attribute, declared as three `vec3` columns at consecutive locations.
+pub struct Mat3(pub [[f32; 3]; 3]);
+
+/// A column major `mat4` attribute, declared as four `vec4` columns at consecutive locations.
+pub struct Mat4(pub [[f32; 4]; 4]);
+
+macro_rules! impl_matrix_ToGlAttrib {
+    ($($t:ty => $columns:expr),+) => {
+        $(
+            impl ToGlAttrib for $t {
+                #[inline]
+                fn size() -> usize {
+                    <f32 as ToGlAttrib>::size()
+                }
+
+                #[inline]
+                fn normalized() -> GLboolean {
+                    gl::FALSE
+                }
+
+                #[inline]
+                fn components() -> i32 {
+                    $columns
+                }
+
+                #[inline]
+                fn gl_enum() -> GLenum {
+                    gl::FLOAT
+                }
+
+                #[inline]
+                fn locations() -> u32 {
+                    $columns
+                }
+            }
+        )+
+    }
+}
+
+impl_matrix_ToGlAttrib! {
+    Mat3 => 3,
+    Mat4 => 4
+}
+
+/// Four signed components packed into one 32 bit integer as `GL_INT_2_10_10_10_REV`.
+///
+/// Usually used as `Normalized<Packed2_10_10_10>` for normals.
+pub struct Packed2_10_10_10(pub u32);
+
+macro_rules! impl_packed_ToGlAttrib {
+    ($($yesno:ident { $t:ty => $gl_enum:expr }),+) => {
+        $(
+            impl ToGlAttrib for $t {
+                #[inline]
+                fn size() -> usize {
+                    4
+                }
+
+                #[inline]
+                fn normalized() -> GLboolean {
+                    gl::$yesno
+                }
+
+                #[inline]
+                fn components() -> i32 {
+                    4
+                }
+
+                #[inline]
+                fn gl_enum() -> GLenum {
+                    $gl_enum
+                }
+
+                #[inline]
+                fn byte_size() -> usize {
+                    4
+                }
+            }
+        )+
+    }
+}
+
+/// A normalized unsigned byte color stored in BGRA order, declared with a size of `GL_BGRA`.
+pub struct Bgra(pub [u8; 4]);
+
+impl ToGlAttrib for Bgra {
+    #[inline]
+    fn size() -> usize {
+        1
+    }
+
+    #[inline]
+    fn normalized() -> GLboolean {
+        // GL requires BGRA attributes to be normalized
+        gl::TRUE
+    }
+
+    #[inline]
+    fn components() -> i32 {
+        4
+    }
+
+    #[inline]
+    fn gl_enum() -> GLenum {
+        gl::UNSIGNED_BYTE
+    }
+
+    #[inline]
+    fn gl_size() -> GLint {
+        gl::BGRA as GLint
+    }
+}
+
+impl_packed_ToGlAttrib! {
+    FALSE { Packed2_10_10_10 => gl::INT_2_10_10_10_REV },
+    TRUE { Normalized<Packed2_10_10_10> => gl::INT_2_10_10_10_REV }
+}
+
 macro_rules! expand_ToGlAttrib_impls {
     (
-        @norm $yesno:ident { $t:ty => $gl_enum:expr }
+        @norm $yesno:ident @kind $kind:ident { $t:ty => $gl_enum:expr }
     ) => {
         impl ToGlAttrib for $t {
             #[inline]
@@ -116,6 +749,11 @@ macro_rules! expand_ToGlAttrib_impls {
             fn gl_enum() -> GLenum {
                 $gl_enum
             }
+
+            #[inline]
+            fn pointer_kind() -> PointerKind {
+                PointerKind::$kind
+            }
         }
 
         impl ToGlAttrib for [$t; 2] {
@@ -138,6 +776,11 @@ macro_rules! expand_ToGlAttrib_impls {
             fn gl_enum() -> GLenum {
                 <$t as ToGlAttrib>::gl_enum()
             }
+
+            #[inline]
+            fn pointer_kind() -> PointerKind {
+                PointerKind::$kind
+            }
         }
 
         impl ToGlAttrib for [$t; 3] {
@@ -160,6 +803,11 @@ macro_rules! expand_ToGlAttrib_impls {
             fn gl_enum() -> GLenum {
                 <$t as ToGlAttrib>::gl_enum()
             }
+
+            #[inline]
+            fn pointer_kind() -> PointerKind {
+                PointerKind::$kind
+            }
         }
 
         impl ToGlAttrib for [$t; 4] {
@@ -182,6 +830,11 @@ macro_rules! expand_ToGlAttrib_impls {
             fn gl_enum() -> GLenum {
                 <$t as ToGlAttrib>::gl_enum()
             }
+
+            #[inline]
+            fn pointer_kind() -> PointerKind {
+                PointerKind::$kind
+            }
         }
     }
 }
@@ -193,7 +846,7 @@ macro_rules! impl_ToGlAttrib {
         ),+
     ) => {
         $(
-            expand_ToGlAttrib_impls!(@norm FALSE { $t => $gl_enum });
+            expand_ToGlAttrib_impls!(@norm FALSE @kind Float { $t => $gl_enum });
         )+
     }
 }
@@ -206,20 +859,45 @@ macro_rules! impl_ToNormalizedGlAttrib {
         ),+
     ) => {
         $(
-            expand_ToGlAttrib_impls!(@norm TRUE { Normalized<$t> => $gl_enum });
+            expand_ToGlAttrib_impls!(@norm TRUE @kind Float { Normalized<$t> => $gl_enum });
         )+
     }
 }
 
-impl_ToGlAttrib! {
-    i8 => gl::BYTE,
-    i16 => gl::SHORT,
-    i32 => gl::INT,
-    u8 => gl::UNSIGNED_BYTE,
-    u16 => gl::UNSIGNED_SHORT,
-    u32 => gl::UNSIGNED_INT,
-    f32 => gl::FLOAT,
+macro_rules! impl_ToIntegerGlAttrib {
+    (
+        $(
+            $t:ty => $gl_enum:expr
+        ),+
+    ) => {
+        $(
+            expand_ToGlAttrib_impls!(@norm FALSE @kind Integer { Integer<$t> => $gl_enum });
+        )+
+    }
+}
+
+macro_rules! impl_ToLongGlAttrib {
+    (
+        $(
+            $t:ty => $gl_enum:expr
+        ),+
+    ) => {
+        $(
+            expand_ToGlAttrib_impls!(@norm FALSE @kind Long { Long<$t> => $gl_enum });
+        )+
+    }
+}
+
+impl_ToGlAttrib! {
+    i8 => gl::BYTE,
+    i16 => gl::SHORT,
+    i32 => gl::INT,
+    u8 => gl::UNSIGNED_BYTE,
+    u16 => gl::UNSIGNED_SHORT,
+    u32 => gl::UNSIGNED_INT,
+    f32 => gl::FLOAT,
     f64 => gl::DOUBLE,
+    Half => gl::HALF_FLOAT,
     bool => gl::BYTE
 }
 
@@ -232,6 +910,306 @@ impl_ToNormalizedGlAttrib! {
     u32 => gl::UNSIGNED_INT
 }
 
+impl_ToIntegerGlAttrib! {
+    i8 => gl::BYTE,
+    i16 => gl::SHORT,
+    i32 => gl::INT,
+    u8 => gl::UNSIGNED_BYTE,
+    u16 => gl::UNSIGNED_SHORT,
+    u32 => gl::UNSIGNED_INT
+}
+
+impl_ToLongGlAttrib! {
+    f64 => gl::DOUBLE
+}
+
+/// Builds a single interleaved buffer layout at runtime, for formats that are only known once
+/// the data is loaded.
+///
+/// Offsets and stride follow the same padding rules as `buffer_layout!`, so
+/// `LayoutBuilder::new().attribute(3, gl::FLOAT, false)` matches `buffer_layout!([f32; 3])`.
+#[derive(Debug, Clone, Default)]
+pub struct LayoutBuilder {
+    attributes: Vec<(i32, GLenum, bool)>,
+}
+
+impl LayoutBuilder {
+    pub fn new() -> LayoutBuilder {
+        LayoutBuilder::default()
+    }
+
+    /// Append an attribute with `components` values of `gl_type` (e.g. `gl::FLOAT`).
+    ///
+    /// # Panics
+    ///
+    /// If `gl_type` is not a vertex attribute type.
+    pub fn attribute(mut self, components: i32, gl_type: GLenum, normalized: bool)
+        -> LayoutBuilder
+    {
+        // validate eagerly so the panic points at the offending call
+        runtime_type_size(gl_type);
+        self.attributes.push((components, gl_type, normalized));
+        self
+    }
+
+    pub fn stride(&self) -> i32 {
+        let mut total = 0;
+        let mut max_alignment = 1;
+        for &(components, gl_type, _) in &self.attributes {
+            let (size, alignment) = runtime_attrib_size(components, gl_type);
+            total += runtime_padding(total, alignment) + size;
+            max_alignment = max(max_alignment, alignment);
+        }
+        (total + runtime_padding(total, max_alignment)) as i32
+    }
+
+    pub fn offsets(&self) -> Vec<usize> {
+        let mut offset = 0;
+        self.attributes.iter()
+            .map(|&(components, gl_type, _)| {
+                let (size, alignment) = runtime_attrib_size(components, gl_type);
+                offset += runtime_padding(offset, alignment);
+                let attrib_offset = offset;
+                offset += size;
+                attrib_offset
+            })
+            .collect()
+    }
+
+    pub fn count(&self) -> u32 {
+        self.attributes.len() as u32
+    }
+
+    /// Enable and declare each attribute at consecutive locations starting at `first_location`,
+    /// returning the next unused location.
+    pub fn declare(&self, first_location: u32) -> u32 {
+        debug_check_locations(first_location + self.count());
+        let stride = self.stride();
+        let offsets = self.offsets();
+        for (i, (&(components, gl_type, normalized), offset)) in
+            self.attributes.iter().zip(offsets).enumerate()
+        {
+            let location = first_location + i as u32;
+            unsafe {
+                gl::EnableVertexAttribArray(location);
+                gl::VertexAttribPointer(location, components, gl_type, normalized as GLboolean,
+                                        stride, offset as *const _);
+            }
+        }
+        first_location + self.count()
+    }
+}
+
+/// The size in bytes of one component of `gl_type`.
+fn runtime_type_size(gl_type: GLenum) -> usize {
+    match type_size(gl_type) {
+        Some(size) => size,
+        None => panic!("unsupported vertex attribute type: {:#x}", gl_type),
+    }
+}
+
+/// The size in bytes of one component of `gl_type`, if it is a vertex attribute type.
+const fn type_size(gl_type: GLenum) -> Option<usize> {
+    Some(match gl_type {
+        gl::BYTE | gl::UNSIGNED_BYTE => 1,
+        gl::SHORT | gl::UNSIGNED_SHORT | gl::HALF_FLOAT => 2,
+        gl::INT | gl::UNSIGNED_INT | gl::FLOAT | gl::FIXED => 4,
+        gl::INT_2_10_10_10_REV | gl::UNSIGNED_INT_2_10_10_10_REV => 4,
+        gl::DOUBLE => 8,
+        _ => return None,
+    })
+}
+
+/// The `(size, alignment)` in bytes of a runtime attribute, where `components` is the `size`
+/// argument to `glVertexAttribPointer`.
+const fn runtime_attrib_size(components: i32, gl_type: GLenum) -> (usize, usize) {
+    let size = match type_size(gl_type) {
+        Some(size) => size,
+        None => panic!("unsupported vertex attribute type"),
+    };
+    match gl_type {
+        // all components are packed into a single integer
+        gl::INT_2_10_10_10_REV | gl::UNSIGNED_INT_2_10_10_10_REV => (size, size),
+        _ if components == gl::BGRA as GLint => (size * 4, size),
+        _ => (size * components as usize, size),
+    }
+}
+
+const fn runtime_padding(offset: usize, alignment: usize) -> usize {
+    (alignment - offset % alignment) % alignment
+}
+
+/// A single attribute location: the arguments of one `glVertexAttrib*Pointer` call.
+///
+/// Build these with `AttributeDesc::new` and the chained modifiers, then lay them out with
+/// `Layout::new`, which fills in `offset` and `stride`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttributeDesc {
+    /// The `size` argument: the number of components, or `gl::BGRA`.
+    pub components: GLint,
+    pub gl_enum: GLenum,
+    pub normalized: bool,
+    pub pointer_kind: PointerKind,
+    /// 0 means the attribute advances once per vertex.
+    pub divisor: u32,
+    pub offset: usize,
+    pub stride: i32,
+}
+
+impl AttributeDesc {
+    /// A float attribute of `components` values of `gl_enum`, not yet laid out.
+    pub const fn new(components: GLint, gl_enum: GLenum) -> AttributeDesc {
+        AttributeDesc {
+            components,
+            gl_enum,
+            normalized: false,
+            pointer_kind: PointerKind::Float,
+            divisor: 0,
+            offset: 0,
+            stride: 0,
+        }
+    }
+
+    pub const fn normalized(mut self) -> AttributeDesc {
+        self.normalized = true;
+        self
+    }
+
+    /// Declare with `glVertexAttribIPointer`, like `Integer<T>`.
+    pub const fn integer(mut self) -> AttributeDesc {
+        self.pointer_kind = PointerKind::Integer;
+        self
+    }
+
+    /// Declare with `glVertexAttribLPointer`, like `Long<T>`.
+    pub const fn long(mut self) -> AttributeDesc {
+        self.pointer_kind = PointerKind::Long;
+        self
+    }
+
+    /// Advance once per `divisor` instances, like `Instanced<T, DIVISOR>`.
+    pub const fn instanced(mut self, divisor: u32) -> AttributeDesc {
+        self.divisor = divisor;
+        self
+    }
+
+    /// Enable and declare this attribute at `location` of the bound VAO, sourced from the buffer
+    /// bound to `gl::ARRAY_BUFFER`.
+    pub fn declare(&self, location: u32) {
+        unsafe {
+            gl::EnableVertexAttribArray(location);
+            match self.pointer_kind {
+                PointerKind::Float => {
+                    gl::VertexAttribPointer(location, self.components, self.gl_enum,
+                                            self.normalized as GLboolean, self.stride,
+                                            self.offset as *const _);
+                },
+                PointerKind::Integer => {
+                    gl::VertexAttribIPointer(location, self.components, self.gl_enum,
+                                             self.stride, self.offset as *const _);
+                },
+                PointerKind::Long => {
+                    gl::VertexAttribLPointer(location, self.components, self.gl_enum,
+                                             self.stride, self.offset as *const _);
+                },
+            }
+            if self.divisor != 0 {
+                gl::VertexAttribDivisor(location, self.divisor);
+            }
+        }
+    }
+}
+
+/// A vertex layout of `N` attribute locations, computed entirely at compile time.
+///
+/// This is an alternative to `buffer_layout!` that follows the same padding rules, but whose
+/// mistakes produce ordinary type errors rather than errors about nested `Attribute` types.
+///
+/// ```rust
+/// # extern crate gl;
+/// # extern crate rustic_gl;
+/// use rustic_gl::attributes::{AttributeDesc, Layout};
+/// # fn main() {
+///
+/// const STATIC_MESH: Layout<2> = Layout::new([
+///     AttributeDesc::new(3, gl::FLOAT),
+///     AttributeDesc::new(4, gl::UNSIGNED_BYTE).normalized(),
+/// ]);
+/// const _: () = assert!(STATIC_MESH.stride() == 16);
+/// // STATIC_MESH.declare(0);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout<const N: usize> {
+    attributes: [AttributeDesc; N],
+    stride: i32,
+}
+
+impl<const N: usize> Layout<N> {
+    /// Lay out `attributes` in order, overwriting their `offset` and `stride`.
+    ///
+    /// # Panics
+    ///
+    /// If an attribute's `gl_enum` is not a vertex attribute type; in a `const` this is a
+    /// compile error.
+    pub const fn new(mut attributes: [AttributeDesc; N]) -> Layout<N> {
+        let mut offset = 0;
+        let mut max_alignment = 1;
+        let mut i = 0;
+        while i < N {
+            let (size, alignment) = runtime_attrib_size(attributes[i].components,
+                                                      attributes[i].gl_enum);
+            offset += runtime_padding(offset, alignment);
+            attributes[i].offset = offset;
+            offset += size;
+            if alignment > max_alignment {
+                max_alignment = alignment;
+            }
+            i += 1;
+        }
+        let stride = (offset + runtime_padding(offset, max_alignment)) as i32;
+        let mut i = 0;
+        while i < N {
+            attributes[i].stride = stride;
+            i += 1;
+        }
+        Layout { attributes, stride }
+    }
+
+    pub const fn stride(&self) -> i32 {
+        self.stride
+    }
+
+    /// The byte offset of each attribute, as `declare` would pass to `glVertexAttribPointer`.
+    pub const fn offsets(&self) -> [usize; N] {
+        let mut offsets = [0; N];
+        let mut i = 0;
+        while i < N {
+            offsets[i] = self.attributes[i].offset;
+            i += 1;
+        }
+        offsets
+    }
+
+    pub const fn count(&self) -> u32 {
+        N as u32
+    }
+
+    pub const fn attributes(&self) -> &[AttributeDesc; N] {
+        &self.attributes
+    }
+
+    /// Enable and declare each attribute at consecutive locations starting at `first_location`,
+    /// returning the next unused location.
+    pub fn declare(&self, first_location: u32) -> u32 {
+        debug_check_locations(first_location + self.count());
+        for (i, attribute) in self.attributes.iter().enumerate() {
+            attribute.declare(first_location + i as u32);
+        }
+        first_location + self.count()
+    }
+}
+
 #[macro_export]
 macro_rules! reverse_then_call_buffer_layout_inner {
     ([] $($reversed:tt)*) => {
@@ -293,60 +1271,197 @@ macro_rules! buffer_layout {
 
 #[cfg(test)]
 mod tests {
-    // TODO: Verify that this mock is actually safe and reliable in the context of `cargo test`
-    // Ideally we can just disable threading for these tests.
+    // The mocked GL functions record into thread local state, so each test (which runs on its own
+    // thread) sees only the calls it made itself.
 
+    use super::PointerKind;
     use gl::types::*;
     use gl;
 
-    #[derive(PartialEq, Eq)]
+    use std::cell::RefCell;
+
+    #[derive(Clone, PartialEq, Eq)]
     struct Gl {
-        attributes: [(bool, i32, GLenum, GLboolean, i32, usize); 16]
+        attributes: [(bool, i32, GLenum, GLboolean, i32, usize); 16],
+        pointer_kinds: [Option<PointerKind>; 16],
+        divisors: [u32; 16],
+        bindings: [u32; 16],
+        binding_divisors: [u32; 16],
+        array_buffer: u32,
+        buffers: [u32; 16],
     }
 
-    static mut GL_STATE: Gl = Gl {
+    const DEFAULT_GL: Gl = Gl {
         attributes: [
             (false, 0, 0 as _, gl::FALSE, 0, 0); 16
-        ]
+        ],
+        pointer_kinds: [None; 16],
+        divisors: [0; 16],
+        bindings: [0; 16],
+        binding_divisors: [0; 16],
+        array_buffer: 0,
+        buffers: [0; 16],
     };
 
-    fn enable_attrib(attr: u32) {
-        unsafe {
-            GL_STATE.attributes[attr as usize].0 = true;
-        }
+    thread_local! {
+        static GL_STATE: RefCell<Gl> = const { RefCell::new(DEFAULT_GL) };
+    }
+
+    extern "system" fn enable_attrib(attr: u32) {
+        // like GL, ignore locations past the end rather than panicking, which would abort here
+        GL_STATE.with(|gl| {
+            if let Some(attribute) = gl.borrow_mut().attributes.get_mut(attr as usize) {
+                attribute.0 = true;
+            }
+        });
+    }
+
+    extern "system" fn disable_attrib(attr: u32) {
+        GL_STATE.with(|gl| {
+            if let Some(attribute) = gl.borrow_mut().attributes.get_mut(attr as usize) {
+                attribute.0 = false;
+            }
+        });
     }
 
-    fn attr_ptr(attr: u32, comps: i32, gl_ty: GLenum, norm: GLboolean, stride: i32, ptr: usize) {
+    fn record_pointer(attr: u32, comps: i32, gl_ty: GLenum, norm: GLboolean, stride: i32,
+                      ptr: usize, kind: PointerKind) {
         let attr = attr as usize;
+        GL_STATE.with(|gl| {
+            let mut gl = gl.borrow_mut();
+            if attr >= gl.attributes.len() {
+                return;
+            }
+            gl.attributes[attr].1 = comps;
+            gl.attributes[attr].2 = gl_ty;
+            gl.attributes[attr].3 = norm;
+            gl.attributes[attr].4 = stride;
+            gl.attributes[attr].5 = ptr;
+            gl.pointer_kinds[attr] = Some(kind);
+            gl.buffers[attr] = gl.array_buffer;
+        });
+    }
+
+    extern "system" fn attr_ptr(attr: u32, comps: i32, gl_ty: GLenum, norm: GLboolean,
+                                stride: i32, ptr: usize) {
+        record_pointer(attr, comps, gl_ty, norm, stride, ptr, PointerKind::Float);
+    }
+
+    extern "system" fn attr_i_ptr(attr: u32, comps: i32, gl_ty: GLenum, stride: i32, ptr: usize) {
+        record_pointer(attr, comps, gl_ty, gl::FALSE, stride, ptr, PointerKind::Integer);
+    }
+
+    extern "system" fn attr_l_ptr(attr: u32, comps: i32, gl_ty: GLenum, stride: i32, ptr: usize) {
+        record_pointer(attr, comps, gl_ty, gl::FALSE, stride, ptr, PointerKind::Long);
+    }
+
+    extern "system" fn bind_buffer(_target: GLenum, buffer: GLuint) {
+        GL_STATE.with(|gl| gl.borrow_mut().array_buffer = buffer);
+    }
+
+    extern "system" fn get_integer(pname: GLenum, data: *mut GLint) {
+        let value = match pname {
+            gl::MAX_VERTEX_ATTRIBS => 16,
+            _ => 0,
+        };
         unsafe {
-            GL_STATE.attributes[attr].1 = comps;
-            GL_STATE.attributes[attr].2 = gl_ty;
-            GL_STATE.attributes[attr].3 = norm;
-            GL_STATE.attributes[attr].4 = stride;
-            GL_STATE.attributes[attr].5 = ptr;
+            *data = value;
         }
     }
 
+    extern "system" fn attr_divisor(attr: u32, divisor: u32) {
+        GL_STATE.with(|gl| {
+            if let Some(d) = gl.borrow_mut().divisors.get_mut(attr as usize) {
+                *d = divisor;
+            }
+        });
+    }
+
+    extern "system" fn enable_vao_attrib(_vao: GLuint, attr: u32) {
+        enable_attrib(attr);
+    }
+
+    extern "system" fn vao_attr_format(_vao: GLuint, attr: u32, comps: i32, gl_ty: GLenum,
+                                       norm: GLboolean, offset: GLuint) {
+        record_pointer(attr, comps, gl_ty, norm, 0, offset as usize, PointerKind::Float);
+    }
+
+    extern "system" fn vao_attr_i_format(_vao: GLuint, attr: u32, comps: i32, gl_ty: GLenum,
+                                         offset: GLuint) {
+        record_pointer(attr, comps, gl_ty, gl::FALSE, 0, offset as usize, PointerKind::Integer);
+    }
+
+    extern "system" fn vao_attr_l_format(_vao: GLuint, attr: u32, comps: i32, gl_ty: GLenum,
+                                         offset: GLuint) {
+        record_pointer(attr, comps, gl_ty, gl::FALSE, 0, offset as usize, PointerKind::Long);
+    }
+
+    extern "system" fn vao_attr_binding(_vao: GLuint, attr: u32, binding: u32) {
+        GL_STATE.with(|gl| {
+            if let Some(b) = gl.borrow_mut().bindings.get_mut(attr as usize) {
+                *b = binding;
+            }
+        });
+    }
+
+    extern "system" fn vao_binding_divisor(_vao: GLuint, binding: u32, divisor: u32) {
+        GL_STATE.with(|gl| {
+            if let Some(d) = gl.borrow_mut().binding_divisors.get_mut(binding as usize) {
+                *d = divisor;
+            }
+        });
+    }
+
+    extern "system" fn attr_format(attr: u32, comps: i32, gl_ty: GLenum, norm: GLboolean,
+                                   offset: GLuint) {
+        vao_attr_format(0, attr, comps, gl_ty, norm, offset);
+    }
+
+    extern "system" fn attr_i_format(attr: u32, comps: i32, gl_ty: GLenum, offset: GLuint) {
+        vao_attr_i_format(0, attr, comps, gl_ty, offset);
+    }
+
+    extern "system" fn attr_l_format(attr: u32, comps: i32, gl_ty: GLenum, offset: GLuint) {
+        vao_attr_l_format(0, attr, comps, gl_ty, offset);
+    }
+
+    extern "system" fn attr_binding(attr: u32, binding: u32) {
+        vao_attr_binding(0, attr, binding);
+    }
+
+    extern "system" fn binding_divisor(binding: u32, divisor: u32) {
+        vao_binding_divisor(0, binding, divisor);
+    }
+
     fn mock_gl() {
         gl::EnableVertexAttribArray::load_with(|_| enable_attrib as *const _);
+        gl::DisableVertexAttribArray::load_with(|_| disable_attrib as *const _);
         gl::VertexAttribPointer::load_with(|_| attr_ptr as *const _);
+        gl::VertexAttribIPointer::load_with(|_| attr_i_ptr as *const _);
+        gl::VertexAttribLPointer::load_with(|_| attr_l_ptr as *const _);
+        gl::VertexAttribDivisor::load_with(|_| attr_divisor as *const _);
+        gl::BindBuffer::load_with(|_| bind_buffer as *const _);
+        gl::GetIntegerv::load_with(|_| get_integer as *const _);
+        gl::EnableVertexArrayAttrib::load_with(|_| enable_vao_attrib as *const _);
+        gl::VertexArrayAttribFormat::load_with(|_| vao_attr_format as *const _);
+        gl::VertexArrayAttribIFormat::load_with(|_| vao_attr_i_format as *const _);
+        gl::VertexArrayAttribLFormat::load_with(|_| vao_attr_l_format as *const _);
+        gl::VertexArrayAttribBinding::load_with(|_| vao_attr_binding as *const _);
+        gl::VertexArrayBindingDivisor::load_with(|_| vao_binding_divisor as *const _);
+        gl::VertexAttribFormat::load_with(|_| attr_format as *const _);
+        gl::VertexAttribIFormat::load_with(|_| attr_i_format as *const _);
+        gl::VertexAttribLFormat::load_with(|_| attr_l_format as *const _);
+        gl::VertexAttribBinding::load_with(|_| attr_binding as *const _);
+        gl::VertexBindingDivisor::load_with(|_| binding_divisor as *const _);
     }
 
     fn setup() {
-        unsafe {
-            GL_STATE = Gl {
-                attributes: [
-                    (false, 0, 0 as _, gl::FALSE, 0, 0); 16
-                ]
-            };
-        }
+        GL_STATE.with(|gl| *gl.borrow_mut() = DEFAULT_GL);
         mock_gl();
     }
 
-    fn state() -> &'static Gl {
-        unsafe {
-            &GL_STATE
-        }
+    fn state() -> Gl {
+        GL_STATE.with(|gl| gl.borrow().clone())
     }
 
     #[test]
@@ -354,10 +1469,10 @@ mod tests {
         setup();
         enable_attrib(0);
         enable_attrib(3);
-        assert_eq!(state().attributes[0].0, true);
-        assert_eq!(state().attributes[1].0, false);
-        assert_eq!(state().attributes[2].0, false);
-        assert_eq!(state().attributes[3].0, true);
+        assert!(state().attributes[0].0);
+        assert!(!state().attributes[1].0);
+        assert!(!state().attributes[2].0);
+        assert!(state().attributes[3].0);
     }
 
     #[test]
@@ -380,6 +1495,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::identity_op)]
     fn supports_a_basic_vertex_format() {
         setup();
         use super::Normalized;
@@ -399,4 +1515,387 @@ mod tests {
         assert_eq!(state().attributes[3], (true, 3, gl::SHORT, gl::TRUE, stride, 24),
             "got state: {:?}", state().attributes[3]);
     }
+
+    #[test]
+    fn integer_attributes_use_the_integer_pointer() {
+        setup();
+        use super::{Integer, Normalized};
+
+        // (vec3 pos, ivec4 bone indices, vec4 bone weights)
+        type Vf = buffer_layout!([f32; 3], [Integer<u8>; 4], [Normalized<u8>; 4]);
+        let stride = Vf::stride();
+        assert_eq!(3 * 4 + 4 + 4, stride as usize);
+
+        Vf::declare(0);
+        let state = state();
+        assert_eq!(state.attributes[1], (true, 4, gl::UNSIGNED_BYTE, gl::FALSE, stride, 12));
+        assert_eq!(state.pointer_kinds[..3],
+            [Some(PointerKind::Float), Some(PointerKind::Integer), Some(PointerKind::Float)]);
+    }
+
+    #[test]
+    fn long_attributes_use_the_long_pointer() {
+        setup();
+        use super::Long;
+
+        type Vf = buffer_layout!([Long<f64>; 3], f32);
+        let stride = Vf::stride();
+        assert_eq!(3 * 8 + 4 + (4), stride as usize);
+
+        Vf::declare(0);
+        let state = state();
+        assert_eq!(state.attributes[0], (true, 3, gl::DOUBLE, gl::FALSE, stride, 0));
+        assert_eq!(state.pointer_kinds[..2], [Some(PointerKind::Long), Some(PointerKind::Float)]);
+    }
+
+    #[test]
+    fn instanced_attributes_set_a_divisor() {
+        setup();
+        use super::{Instanced, Normalized};
+
+        // (vec3 per-instance offset, vec4 color every other instance, float per-vertex)
+        type Vf = buffer_layout!(Instanced<[f32; 3]>, Instanced<[Normalized<u8>; 4], 2>, f32);
+        let stride = Vf::stride();
+        assert_eq!(3 * 4 + 4 + 4, stride as usize);
+
+        Vf::declare(0);
+        let state = state();
+        assert_eq!(state.attributes[1], (true, 4, gl::UNSIGNED_BYTE, gl::TRUE, stride, 12));
+        assert_eq!(state.divisors[..3], [1, 2, 0]);
+    }
+
+    #[test]
+    fn matrix_attributes_span_consecutive_locations() {
+        setup();
+        use super::{Instanced, Mat4};
+
+        type Vf = buffer_layout!([f32; 3], Instanced<Mat4>, f32);
+        let stride = Vf::stride();
+        assert_eq!(3 * 4 + 16 * 4 + 4, stride as usize);
+
+        Vf::declare(0);
+        let state = state();
+        for column in 0..4 {
+            assert_eq!(state.attributes[1 + column],
+                (true, 4, gl::FLOAT, gl::FALSE, stride, 12 + column * 16));
+        }
+        assert_eq!(state.attributes[5], (true, 1, gl::FLOAT, gl::FALSE, stride, 76));
+        assert_eq!(state.divisors[..6], [0, 1, 1, 1, 1, 0]);
+    }
+
+    #[test]
+    fn declare_returns_the_next_free_location() {
+        setup();
+        use super::Mat4;
+
+        type Vertices = buffer_layout!([f32; 3], [f32; 2]);
+        type Instances = buffer_layout!(Mat4, [f32; 4]);
+        let next = Vertices::declare(0);
+        assert_eq!(next, 2);
+        assert_eq!(Instances::declare(next), 7);
+        assert_eq!(state().attributes[6], (true, 4, gl::FLOAT, gl::FALSE, 80, 64));
+    }
+
+    #[test]
+    fn offsets_match_declare() {
+        use super::Normalized;
+
+        type Vf = buffer_layout!([f32; 3], [f32; 2], [Normalized<u8>; 4], [Normalized<i16>; 3]);
+        assert_eq!(Vf::offsets(), vec![0, 12, 20, 24]);
+
+        type Padded = buffer_layout!(f32, i16, i8, i16);
+        assert_eq!(Padded::offsets(), vec![0, 4, 6, 8]);
+    }
+
+    #[test]
+    fn count_matches_declared_locations() {
+        setup();
+        use super::Mat3;
+
+        type Vf = buffer_layout!([f32; 3], Mat3, [f32; 2]);
+        assert_eq!(Vf::count(), 5);
+        assert_eq!(Vf::declare(2), 2 + Vf::count());
+        assert!(state().attributes[2..7].iter().all(|a| a.0));
+        assert!(!state().attributes[7].0);
+    }
+
+    #[test]
+    fn packed_normals_take_four_bytes() {
+        setup();
+        use super::{Normalized, Packed2_10_10_10};
+
+        type Vf = buffer_layout!([f32; 3], Normalized<Packed2_10_10_10>, [f32; 2]);
+        let stride = Vf::stride();
+        assert_eq!(3 * 4 + 4 + 2 * 4, stride as usize);
+        assert_eq!(Vf::offsets(), vec![0, 12, 16]);
+
+        Vf::declare(0);
+        assert_eq!(state().attributes[1],
+            (true, 4, gl::INT_2_10_10_10_REV, gl::TRUE, stride, 12));
+    }
+
+    #[test]
+    fn supports_half_floats() {
+        setup();
+        use super::Half;
+
+        type Vf = buffer_layout!([Half; 3], [Half; 2]);
+        let stride = Vf::stride();
+        assert_eq!(3 * 2 + 2 * 2, stride as usize);
+
+        Vf::declare(0);
+        assert_eq!(state().attributes[1], (true, 2, gl::HALF_FLOAT, gl::FALSE, stride, 6));
+    }
+
+    #[test]
+    fn declares_formats_with_direct_state_access() {
+        setup();
+        use super::{Instanced, Integer, Mat4};
+
+        type Vf = buffer_layout!(Instanced<Mat4>, Instanced<[Integer<i32>; 2]>);
+        assert_eq!(Vf::declare_dsa(1, 3, 2), 7);
+
+        let state = state();
+        assert_eq!(state.attributes[5], (true, 4, gl::FLOAT, gl::FALSE, 0, 48));
+        assert_eq!(state.attributes[6], (true, 2, gl::INT, gl::FALSE, 0, 64));
+        assert_eq!(state.pointer_kinds[6], Some(PointerKind::Integer));
+        assert_eq!(state.bindings[2..7], [3; 5]);
+        assert_eq!(state.binding_divisors[3], 1);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "must all have the same divisor")]
+    fn declare_dsa_rejects_mixed_divisors() {
+        setup();
+        use super::{Instanced, Mat4};
+
+        type Vf = buffer_layout!(Instanced<Mat4>, [f32; 2]);
+        Vf::declare_dsa(1, 3, 0);
+    }
+
+    #[test]
+    fn declares_formats_relative_to_a_binding() {
+        setup();
+        use super::{Long, Normalized};
+
+        type Vf = buffer_layout!([f32; 3], [Normalized<u8>; 4], Long<f64>);
+        assert_eq!(Vf::declare_format(1, 0), 3);
+
+        let state = state();
+        assert_eq!(state.attributes[1], (true, 4, gl::UNSIGNED_BYTE, gl::TRUE, 0, 12));
+        assert_eq!(state.attributes[2], (true, 1, gl::DOUBLE, gl::FALSE, 0, 16));
+        assert_eq!(state.pointer_kinds[2], Some(PointerKind::Long));
+        assert_eq!(state.bindings[..3], [1; 3]);
+        assert_eq!(state.binding_divisors[1], 0);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "must all have the same divisor")]
+    fn declare_format_rejects_mixed_divisors() {
+        setup();
+        use super::Instanced;
+
+        type Vf = buffer_layout!([f32; 3], Instanced<[f32; 4], 2>);
+        Vf::declare_format(0, 0);
+    }
+
+    #[test]
+    fn layout_builder_matches_buffer_layout() {
+        setup();
+        use super::{LayoutBuilder, Normalized, Packed2_10_10_10};
+
+        type Vf = buffer_layout!([f32; 3], [Normalized<u8>; 3], Normalized<Packed2_10_10_10>,
+                                 i16, [f64; 2], u8);
+        let builder = LayoutBuilder::new()
+            .attribute(3, gl::FLOAT, false)
+            .attribute(3, gl::UNSIGNED_BYTE, true)
+            .attribute(4, gl::INT_2_10_10_10_REV, true)
+            .attribute(1, gl::SHORT, false)
+            .attribute(2, gl::DOUBLE, false)
+            .attribute(1, gl::UNSIGNED_BYTE, false);
+        assert_eq!(builder.stride(), Vf::stride());
+        assert_eq!(builder.offsets(), Vf::offsets());
+
+        assert_eq!(builder.declare(0), 6);
+        let runtime = state();
+        setup();
+        Vf::declare(0);
+        assert!(runtime == state());
+    }
+
+    #[test]
+    fn layout_builder_sizes_bgra_attributes() {
+        setup();
+        use super::{Bgra, LayoutBuilder};
+
+        type Vf = buffer_layout!(u8, Bgra);
+        let builder = LayoutBuilder::new()
+            .attribute(1, gl::UNSIGNED_BYTE, false)
+            .attribute(gl::BGRA as i32, gl::UNSIGNED_BYTE, true);
+        assert_eq!(builder.stride(), Vf::stride());
+        assert_eq!(builder.offsets(), Vf::offsets());
+    }
+
+    #[test]
+    fn packed_attributes_have_no_padding() {
+        setup();
+        use super::Packed;
+        use std::mem::size_of;
+
+        #[allow(dead_code)]
+        #[repr(C, packed)]
+        struct Vertex {
+            flag: u8,
+            position: [f32; 3],
+            id: i16,
+        }
+
+        type Vf = buffer_layout!(Packed<u8>, Packed<[f32; 3]>, Packed<i16>);
+        assert_eq!(Vf::offsets(), vec![0, 1, 13]);
+        assert_eq!(Vf::stride() as usize, size_of::<Vertex>());
+    }
+
+    #[test]
+    fn assert_matches_accepts_the_matching_struct() {
+        #[allow(dead_code)]
+        #[repr(C)]
+        struct Vertex {
+            position: [f32; 3],
+            uv: [f32; 2],
+        }
+
+        type Vf = buffer_layout!([f32; 3], [f32; 2]);
+        Vf::assert_matches::<Vertex>();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "layout stride is 12 bytes")]
+    fn assert_matches_rejects_a_mismatched_struct() {
+        type Vf = buffer_layout!([f32; 3]);
+        Vf::assert_matches::<[f32; 4]>();
+    }
+
+    #[test]
+    fn declare_separate_uses_one_buffer_per_attribute() {
+        setup();
+        use super::Mat3;
+
+        type Vf = buffer_layout!([f32; 3], Mat3, [u16; 2]);
+        Vf::declare_separate(&[(7, 0), (8, 4), (9, 1)]);
+        let state = state();
+        assert_eq!(state.attributes[0], (true, 3, gl::FLOAT, gl::FALSE, 12, 0));
+        assert_eq!(state.attributes[1], (true, 2, gl::UNSIGNED_SHORT, gl::FALSE, 4, 0));
+        assert_eq!(state.attributes[5], (true, 3, gl::FLOAT, gl::FALSE, 36, 12));
+        assert_eq!(state.buffers[..7], [7, 9, 0, 0, 8, 8, 8]);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected a buffer and location for each of 2 attributes")]
+    fn declare_separate_needs_a_source_per_attribute() {
+        type Vf = buffer_layout!([f32; 3], [f32; 2]);
+        Vf::declare_separate(&[(1, 0)]);
+    }
+
+    #[test]
+    fn describe_matches_const_layout() {
+        use super::{AttributeDesc, Instanced, Layout, Mat3, Normalized};
+
+        type Vf = buffer_layout!([f32; 3], [Normalized<u8>; 4], Instanced<Mat3>);
+        let layout = Layout::new([
+            AttributeDesc::new(3, gl::FLOAT),
+            AttributeDesc::new(4, gl::UNSIGNED_BYTE).normalized(),
+            AttributeDesc::new(3, gl::FLOAT).instanced(1),
+            AttributeDesc::new(3, gl::FLOAT).instanced(1),
+            AttributeDesc::new(3, gl::FLOAT).instanced(1),
+        ]);
+        assert_eq!(Vf::describe(), layout.attributes().to_vec());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "layout needs attribute locations up to 17")]
+    fn declare_checks_max_vertex_attribs() {
+        setup();
+        use super::Mat4;
+
+        type Vf = buffer_layout!(Mat4);
+        Vf::declare(13);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "layout needs attribute locations up to 17")]
+    fn declare_separate_checks_max_vertex_attribs() {
+        setup();
+
+        type Vf = buffer_layout!([f32; 3], [f32; 2]);
+        Vf::declare_separate(&[(1, 0), (2, 16)]);
+    }
+
+    #[test]
+    fn declare_with_stride_keeps_offsets() {
+        setup();
+
+        type Vf = buffer_layout!([f32; 3], [f32; 2]);
+        assert_eq!(Vf::declare_with_stride(0, 32), 2);
+        let state = state();
+        assert_eq!(state.attributes[0], (true, 3, gl::FLOAT, gl::FALSE, 32, 0));
+        assert_eq!(state.attributes[1], (true, 2, gl::FLOAT, gl::FALSE, 32, 12));
+    }
+
+    #[test]
+    fn const_layout_matches_buffer_layout() {
+        setup();
+        use super::{AttributeDesc, Bgra, Instanced, Integer, Layout, Normalized};
+
+        type Vf = buffer_layout!([f32; 3], [Normalized<u8>; 3], Bgra, Integer<i16>,
+                                 Instanced<[f64; 2]>);
+        const LAYOUT: Layout<5> = Layout::new([
+            AttributeDesc::new(3, gl::FLOAT),
+            AttributeDesc::new(3, gl::UNSIGNED_BYTE).normalized(),
+            AttributeDesc::new(gl::BGRA as GLint, gl::UNSIGNED_BYTE).normalized(),
+            AttributeDesc::new(1, gl::SHORT).integer(),
+            AttributeDesc::new(2, gl::DOUBLE).instanced(1),
+        ]);
+        const STRIDE: i32 = LAYOUT.stride();
+        assert_eq!(STRIDE, Vf::stride());
+        assert_eq!(LAYOUT.offsets().to_vec(), Vf::offsets());
+
+        assert_eq!(LAYOUT.declare(0), 5);
+        let runtime = state();
+        setup();
+        Vf::declare(0);
+        assert!(runtime == state());
+    }
+
+    #[test]
+    fn disable_undoes_declare() {
+        setup();
+        use super::Mat4;
+
+        type Vf = buffer_layout!([f32; 3], Mat4);
+        enable_attrib(0);
+        assert_eq!(Vf::declare(1), 6);
+        assert_eq!(Vf::disable(1), 6);
+        let state = state();
+        assert!(state.attributes[0].0);
+        assert!(state.attributes[1..].iter().all(|a| !a.0));
+    }
+
+    #[test]
+    fn bgra_colors_pass_gl_bgra_as_the_size() {
+        setup();
+        use super::Bgra;
+
+        type Vf = buffer_layout!([f32; 2], Bgra);
+        let stride = Vf::stride();
+        assert_eq!(2 * 4 + 4, stride as usize);
+
+        Vf::declare(0);
+        assert_eq!(state().attributes[1],
+            (true, gl::BGRA as i32, gl::UNSIGNED_BYTE, gl::TRUE, stride, 8));
+    }
 }
@@ -0,0 +1,36 @@
+//! Runtime queries for what the current context supports.
+
+use gl;
+use gl::types::*;
+
+use std::ffi::CStr;
+
+/// The `(major, minor)` version of the current context, e.g. `(4, 5)`.
+///
+/// Relies on `GL_MAJOR_VERSION`, so contexts older than 3.0 report `(0, 0)`.
+pub fn gl_version() -> (u32, u32) {
+    let (mut major, mut minor) = (0, 0);
+    unsafe {
+        gl::GetIntegerv(gl::MAJOR_VERSION, &mut major);
+        gl::GetIntegerv(gl::MINOR_VERSION, &mut minor);
+    }
+    (major as u32, minor as u32)
+}
+
+/// Whether the current context is at least version `major.minor`.
+pub fn gl_version_at_least(major: u32, minor: u32) -> bool {
+    gl_version() >= (major, minor)
+}
+
+/// Whether the current context advertises the extension `name`, e.g. `"GL_KHR_debug"`.
+pub fn has_extension(name: &str) -> bool {
+    let mut count = 0;
+    unsafe {
+        gl::GetIntegerv(gl::NUM_EXTENSIONS, &mut count);
+    }
+    (0..count.max(0) as GLuint).any(|i| {
+        let extension = unsafe { gl::GetStringi(gl::EXTENSIONS, i) };
+        !extension.is_null() &&
+            unsafe { CStr::from_ptr(extension as *const _) }.to_bytes() == name.as_bytes()
+    })
+}
@@ -0,0 +1,103 @@
+//! Helpers for `KHR_debug` (core since OpenGL 4.3).
+
+use gl;
+use gl::types::*;
+
+use error::{GlResult, GlError};
+
+use std::ffi::CStr;
+use std::mem;
+use std::os::raw::c_void;
+use std::slice;
+
+/// A debug message callback, called with `(source, type, id, severity, message)`.
+pub type DebugCallback = fn(GLenum, GLenum, GLuint, GLenum, &str);
+
+/// Enable debug output and forward every message to `callback`.
+///
+/// Output is made synchronous, so `callback` runs on the thread that issued the offending call,
+/// which makes it a convenient place for a breakpoint. Returns `GlError::Unsupported` if the
+/// context does not provide `glDebugMessageCallback`.
+pub fn enable_debug_output(callback: DebugCallback) -> GlResult<()> {
+    if !gl::DebugMessageCallback::is_loaded() {
+        return Err(GlError::Unsupported("KHR_debug"));
+    }
+    unsafe {
+        gl::Enable(gl::DEBUG_OUTPUT);
+        gl::Enable(gl::DEBUG_OUTPUT_SYNCHRONOUS);
+        gl::DebugMessageCallback(debug_trampoline, callback as *const c_void);
+    }
+    Ok(())
+}
+
+extern "system" fn debug_trampoline(source: GLenum, gltype: GLenum, id: GLuint, severity: GLenum,
+                                    length: GLsizei, message: *const GLchar,
+                                    user_param: *mut c_void) {
+    unsafe {
+        let callback: DebugCallback = mem::transmute(user_param);
+        let bytes = if length < 0 {
+            CStr::from_ptr(message).to_bytes()
+        } else {
+            slice::from_raw_parts(message as *const u8, length as usize)
+        };
+        callback(source, gltype, id, severity, &String::from_utf8_lossy(bytes));
+    }
+}
+
+/// Open a named debug group, which shows up as a scope in tools like RenderDoc.
+///
+/// Like the other annotation functions below, this does nothing if the context does not provide
+/// `KHR_debug`, so annotations can be left in unconditionally.
+pub fn push_debug_group(message: &str) {
+    if !gl::PushDebugGroup::is_loaded() {
+        return;
+    }
+    unsafe {
+        gl::PushDebugGroup(gl::DEBUG_SOURCE_APPLICATION, 0, message.len() as GLsizei,
+                           message.as_ptr() as *const GLchar);
+    }
+}
+
+/// Close the innermost group opened with `push_debug_group`.
+pub fn pop_debug_group() {
+    if !gl::PopDebugGroup::is_loaded() {
+        return;
+    }
+    unsafe {
+        gl::PopDebugGroup();
+    }
+}
+
+/// Name the object `name` of type `identifier` (e.g. `gl::BUFFER` or `gl::TEXTURE`) in debug
+/// messages and captures.
+pub fn set_object_label(identifier: GLenum, name: GLuint, label: &str) {
+    if !gl::ObjectLabel::is_loaded() {
+        return;
+    }
+    unsafe {
+        gl::ObjectLabel(identifier, name, label.len() as GLsizei,
+                        label.as_ptr() as *const GLchar);
+    }
+}
+
+/// Keeps a debug group open for as long as the guard lives. Like the guards in [`raw`], it must
+/// be bound to a named variable to stay alive.
+///
+/// [`raw`]: ../raw/index.html
+#[must_use = "the debug group is popped as soon as the guard is dropped"]
+pub struct DebugGroup {
+    _private: (),
+}
+
+impl DebugGroup {
+    pub fn new(message: &str) -> DebugGroup {
+        push_debug_group(message);
+        DebugGroup { _private: () }
+    }
+}
+
+impl Drop for DebugGroup {
+    fn drop(&mut self) {
+        pop_debug_group();
+    }
+}
@@ -1,9 +1,13 @@
+use gl;
+use gl::types::*;
+
 use std::fmt;
 use std::error;
+use std::io;
 
 pub type GlResult<T> = Result<T, GlError>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 #[allow(non_camel_case_types)]
 pub enum GlError {
     ProgramLinkage(Option<String>),
@@ -11,14 +15,41 @@ pub enum GlError {
     ProgramValidation(Option<String>),
     ShaderCreation,
     ShaderCompilation(Option<String>),
-    TextureCreation,
+    /// Optionally describes the failing call, e.g. its target and format.
+    TextureCreation(Option<String>),
     BufferCreation,
     VaoCreation,
+    PipelineCreation,
+    FramebufferCreation,
+    RenderbufferCreation,
+    SamplerCreation,
+    QueryCreation,
+    /// `glClientWaitSync` timed out before the fence was signaled.
+    SyncTimeout,
+    SyncWaitFailed,
+    /// The status returned by `glCheckFramebufferStatus`, e.g. `GL_FRAMEBUFFER_UNSUPPORTED`.
+    FramebufferIncomplete(GLenum),
+    /// The kind and message of an `io::Error`, kept as plain data so `GlError` stays `Clone`.
+    Io(io::ErrorKind, String),
+    IncludeCycle(String),
+    Unsupported(&'static str),
+    /// A named program resource (e.g. a uniform block) does not exist or is not active.
+    NotFound(String),
     GL_INVALID_ENUM,
     GL_INVALID_VALUE,
     GL_INVALID_OPERATION,
     GL_OUT_OF_MEMORY,
-    GL_UNKNOWN_ERROR
+    GL_STACK_OVERFLOW,
+    GL_STACK_UNDERFLOW,
+    GL_INVALID_FRAMEBUFFER_OPERATION,
+    GL_UNKNOWN_ERROR,
+    /// An error annotated with what was being done when it occurred; see `GlResultExt`.
+    ///
+    /// Displays as `message: source`.
+    Context {
+        message: String,
+        source: Box<GlError>,
+    }
 }
 
 impl fmt::Display for GlError {
@@ -36,22 +67,150 @@ impl fmt::Display for GlError {
                 write!(f, "RenderError: Program validation failed. Log:\n{}",
                     log.clone().unwrap_or("No log".to_string()))
             },
+            GlError::TextureCreation(Some(ref message)) => {
+                write!(f, "RenderError: {}: {}", self.as_str(), message)
+            },
+            GlError::Context { ref message, ref source } => {
+                write!(f, "{}: {}", message, source)
+            },
+            GlError::Io(_, ref message) => {
+                write!(f, "RenderError: {}: {}", self.as_str(), message)
+            },
+            GlError::IncludeCycle(ref name) => {
+                write!(f, "RenderError: {} at \"{}\"", self.as_str(), name)
+            },
+            GlError::NotFound(ref name) => {
+                write!(f, "RenderError: {}: \"{}\"", self.as_str(), name)
+            },
+            GlError::FramebufferIncomplete(status) => {
+                write!(f, "RenderError: {}: {}", self.as_str(), framebuffer_status_name(status))
+            },
+            GlError::Unsupported(feature) => {
+                write!(f, "RenderError: {}: {}", self.as_str(), feature)
+            },
             _ => write!(f, "RenderError: {}", self.as_str())
         }
     }
 }
 
+fn framebuffer_status_name(status: GLenum) -> String {
+    let name = match status {
+        gl::FRAMEBUFFER_UNDEFINED => "GL_FRAMEBUFFER_UNDEFINED",
+        gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT => "GL_FRAMEBUFFER_INCOMPLETE_ATTACHMENT",
+        gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => {
+            "GL_FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT"
+        },
+        gl::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER => "GL_FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER",
+        gl::FRAMEBUFFER_INCOMPLETE_READ_BUFFER => "GL_FRAMEBUFFER_INCOMPLETE_READ_BUFFER",
+        gl::FRAMEBUFFER_UNSUPPORTED => "GL_FRAMEBUFFER_UNSUPPORTED",
+        gl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => "GL_FRAMEBUFFER_INCOMPLETE_MULTISAMPLE",
+        gl::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS => "GL_FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS",
+        _ => return format!("0x{:04X}", status),
+    };
+    name.to_string()
+}
+
 impl error::Error for GlError {
-    fn description(&self) -> &str {
-        "A render error occured"
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            GlError::Context { ref source, .. } => Some(&**source),
+            _ => None,
+        }
+    }
+}
+
+/// Adds `context` to `GlResult`, for annotating errors as they propagate.
+pub trait GlResultExt<T> {
+    /// Wrap an error in `GlError::Context` with `message`, e.g. `"loading terrain shader"`.
+    fn context(self, message: &str) -> GlResult<T>;
+}
+
+impl<T> GlResultExt<T> for GlResult<T> {
+    fn context(self, message: &str) -> GlResult<T> {
+        self.map_err(|err| GlError::Context {
+            message: message.to_string(),
+            source: Box::new(err),
+        })
+    }
+}
+
+impl From<io::Error> for GlError {
+    fn from(err: io::Error) -> GlError {
+        GlError::Io(err.kind(), err.to_string())
     }
+}
 
-    fn cause(&self) -> Option<&error::Error> {
-        None
+/// Read `glGetError` once, returning the matching `GlError` if an error was pending.
+pub fn check_error() -> GlResult<()> {
+    match unsafe { gl::GetError() } {
+        gl::NO_ERROR => Ok(()),
+        code => Err(GlError::from_gl_enum(code))
     }
 }
 
+/// Drain every pending GL error, returning the first one encountered.
+///
+/// GL may record several errors (e.g. one per context flag) before they are read.
+pub fn check_error_all() -> GlResult<()> {
+    // without a current context some implementations report an error forever, so cap the loop
+    const MAX_PENDING_ERRORS: usize = 64;
+    let first = check_error();
+    if first.is_err() {
+        for _ in 1..MAX_PENDING_ERRORS {
+            if check_error().is_ok() {
+                break;
+            }
+        }
+    }
+    first
+}
+
+/// Evaluate a GL call and, in debug builds, panic with the call site if it raised an error.
+///
+/// In release builds this expands to just the expression. The expression is not wrapped in
+/// `unsafe`, so `gl` calls must still be made from an `unsafe` block:
+///
+/// ```rust,no_run
+/// # #[macro_use]
+/// # extern crate rustic_gl;
+/// # extern crate gl;
+/// # fn main() {
+/// unsafe {
+///     gl_check!(gl::DrawArrays(gl::TRIANGLES, 0, 3));
+/// }
+/// # }
+/// ```
+#[macro_export]
+macro_rules! gl_check {
+    ($call:expr) => {{
+        let result = $call;
+        #[cfg(debug_assertions)]
+        {
+            if let Err(err) = $crate::error::check_error() {
+                panic!("{} ({}) at {}:{}", err, stringify!($call), file!(), line!());
+            }
+        }
+        result
+    }};
+}
+
 impl GlError {
+    /// Convert an error code returned by `glGetError` into the matching variant.
+    ///
+    /// Unrecognized codes (including `GL_NO_ERROR`) become `GL_UNKNOWN_ERROR`.
+    pub fn from_gl_enum(code: GLenum) -> GlError {
+        match code {
+            gl::INVALID_ENUM => GlError::GL_INVALID_ENUM,
+            gl::INVALID_VALUE => GlError::GL_INVALID_VALUE,
+            gl::INVALID_OPERATION => GlError::GL_INVALID_OPERATION,
+            gl::OUT_OF_MEMORY => GlError::GL_OUT_OF_MEMORY,
+            gl::STACK_OVERFLOW => GlError::GL_STACK_OVERFLOW,
+            gl::STACK_UNDERFLOW => GlError::GL_STACK_UNDERFLOW,
+            gl::INVALID_FRAMEBUFFER_OPERATION => GlError::GL_INVALID_FRAMEBUFFER_OPERATION,
+            _ => GlError::GL_UNKNOWN_ERROR
+        }
+    }
+
     fn as_str(&self) -> &str {
         match *self {
             GlError::ProgramLinkage(_) => "program linking failed",
@@ -59,14 +218,121 @@ impl GlError {
             GlError::ProgramValidation(_) => "program validation failed",
             GlError::ShaderCreation => "shader creation failed",
             GlError::ShaderCompilation(_) => "shader compilation failed",
-            GlError::TextureCreation => "texture creation failed",
+            GlError::TextureCreation(_) => "texture creation failed",
             GlError::BufferCreation => "buffer creation failed",
-            GlError::VaoCreation => "VAO creation failed",
+            GlError::VaoCreation => "vao creation failed",
+            GlError::PipelineCreation => "program pipeline creation failed",
+            GlError::FramebufferCreation => "framebuffer creation failed",
+            GlError::FramebufferIncomplete(_) => "framebuffer incomplete",
+            GlError::RenderbufferCreation => "renderbuffer creation failed",
+            GlError::SamplerCreation => "sampler creation failed",
+            GlError::QueryCreation => "query creation failed",
+            GlError::SyncTimeout => "timed out waiting for sync object",
+            GlError::SyncWaitFailed => "waiting for sync object failed",
+            GlError::Io(..) => "io error",
+            GlError::IncludeCycle(_) => "include cycle detected",
+            GlError::Unsupported(_) => "unsupported by this context",
+            GlError::NotFound(_) => "no such active resource in program",
             GlError::GL_INVALID_ENUM => "GL_INVALID_ENUM",
             GlError::GL_INVALID_VALUE => "GL_INVALID_VALUE",
             GlError::GL_INVALID_OPERATION => "GL_INVALID_OPERATION",
             GlError::GL_OUT_OF_MEMORY => "GL_OUT_OF_MEMORY",
-            GlError::GL_UNKNOWN_ERROR => "GL_UNKNOWN_ERROR"
+            GlError::GL_STACK_OVERFLOW => "GL_STACK_OVERFLOW",
+            GlError::GL_STACK_UNDERFLOW => "GL_STACK_UNDERFLOW",
+            GlError::GL_INVALID_FRAMEBUFFER_OPERATION => "GL_INVALID_FRAMEBUFFER_OPERATION",
+            GlError::GL_UNKNOWN_ERROR => "GL_UNKNOWN_ERROR",
+            GlError::Context { ref message, .. } => message
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{GlError, GlResult, GlResultExt, check_error, check_error_all};
+
+    use gl;
+    use gl::types::*;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const PENDING: [GLenum; 3] = [gl::INVALID_VALUE, gl::OUT_OF_MEMORY, gl::NO_ERROR];
+    static NEXT_ERROR: AtomicUsize = AtomicUsize::new(0);
+
+    extern "system" fn mock_get_error() -> GLenum {
+        let i = NEXT_ERROR.fetch_add(1, Ordering::SeqCst);
+        PENDING[i.min(PENDING.len() - 1)]
+    }
+
+    #[test]
+    fn check_error_all_drains_and_returns_the_first_error() {
+        gl::GetError::load_with(|_| mock_get_error as *const _);
+        match check_error_all() {
+            Err(GlError::GL_INVALID_VALUE) => {},
+            other => panic!("expected GL_INVALID_VALUE, got {:?}", other),
+        }
+        assert!(check_error().is_ok());
+    }
+
+    #[test]
+    fn maps_gl_error_codes() {
+        let name = |code| GlError::from_gl_enum(code).to_string();
+        assert_eq!(name(gl::INVALID_ENUM), "RenderError: GL_INVALID_ENUM");
+        assert_eq!(name(gl::INVALID_OPERATION), "RenderError: GL_INVALID_OPERATION");
+        assert_eq!(name(gl::STACK_UNDERFLOW), "RenderError: GL_STACK_UNDERFLOW");
+        assert_eq!(name(gl::INVALID_FRAMEBUFFER_OPERATION),
+                   "RenderError: GL_INVALID_FRAMEBUFFER_OPERATION");
+        assert_eq!(name(0xdead), "RenderError: GL_UNKNOWN_ERROR");
+    }
+
+    #[test]
+    fn converts_io_errors() {
+        use std::io;
+
+        let err = GlError::from(io::Error::new(io::ErrorKind::NotFound, "missing.vert"));
+        assert_eq!(err, GlError::Io(io::ErrorKind::NotFound, "missing.vert".to_string()));
+        assert_eq!(err.clone().to_string(), "RenderError: io error: missing.vert");
+    }
+
+    #[test]
+    fn compares_log_payloads() {
+        assert_eq!(GlError::ShaderCompilation(None), GlError::ShaderCompilation(None));
+        assert_ne!(GlError::ShaderCompilation(Some("a".to_string())),
+                   GlError::ShaderCompilation(Some("b".to_string())));
+    }
+
+    #[test]
+    fn displays_vao_creation() {
+        assert_eq!(GlError::VaoCreation.to_string(), "RenderError: vao creation failed");
+    }
+
+    #[test]
+    fn displays_texture_creation_message() {
+        assert_eq!(GlError::TextureCreation(None).to_string(),
+                   "RenderError: texture creation failed");
+        assert_eq!(GlError::TextureCreation(Some("GL_TEXTURE_2D".to_string())).to_string(),
+                   "RenderError: texture creation failed: GL_TEXTURE_2D");
+    }
+
+    #[test]
+    fn context_wraps_the_source_error() {
+        use std::error::Error;
+
+        let result: GlResult<()> = Err(GlError::VaoCreation);
+        let err = result.context("loading terrain").unwrap_err();
+        assert_eq!(err.to_string(), "loading terrain: RenderError: vao creation failed");
+        assert_eq!(err.source().map(|e| e.to_string()),
+                   Some("RenderError: vao creation failed".to_string()));
+
+        let outer = Err::<(), _>(err).context("loading level").unwrap_err();
+        assert_eq!(outer.to_string(),
+                   "loading level: loading terrain: RenderError: vao creation failed");
+    }
+
+    #[test]
+    fn displays_framebuffer_status() {
+        assert_eq!(GlError::FramebufferIncomplete(gl::FRAMEBUFFER_UNSUPPORTED).to_string(),
+                   "RenderError: framebuffer incomplete: GL_FRAMEBUFFER_UNSUPPORTED");
+        assert_eq!(GlError::FramebufferIncomplete(0x1234).to_string(),
+                   "RenderError: framebuffer incomplete: 0x1234");
+    }
+}
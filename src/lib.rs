@@ -8,7 +8,12 @@
 //! useful accross many OpenGL apps, feel free to open a pull request.
 //!
 //! The `raw` module contains basic functions like `create_buffer()` and `create_vao()`, and the
-//! `error` module provides a very basic, boilerplate `GlError` type.
+//! `error` module provides a very basic, boilerplate `GlError` type. The `debug` module forwards
+//! `KHR_debug` messages to a Rust callback, and the `caps` module queries the context's version
+//! and extensions.
+//!
+//! Call `init` with your context's function loader before using anything else. With the default
+//! `loader` feature the `gl` crate is also re-exported, so `rustic_gl::gl::load_with` works too.
 //!
 //! A "goody" included in the library is the `create_basic_program(vertex_src, shader_src)`
 //! function, which may save you a few minutes when making a new project or small toy app.
@@ -18,10 +23,29 @@
 //! framework using the GLSL preprocessor. You could refer to the source of `create_linked_program`
 //! as a starting point.
 
+#[cfg(feature = "loader")]
 pub extern crate gl;
+#[cfg(not(feature = "loader"))]
+extern crate gl;
 
 #[macro_use]
 pub mod attributes;
 
+pub mod caps;
+pub mod debug;
 pub mod error;
 pub mod raw;
+
+use std::os::raw::c_void;
+
+/// Load the GL bindings this crate calls into, along with the extension entry points used by
+/// `raw` (see `raw::load_extensions_with`).
+///
+/// `loader` is typically your windowing library's `get_proc_address`. This is the only way to
+/// load the bindings without the `loader` feature, since `gl` is then not re-exported.
+pub fn init<F>(mut loader: F)
+    where F: FnMut(&str) -> *const c_void
+{
+    gl::load_with(&mut loader);
+    raw::load_extensions_with(loader);
+}
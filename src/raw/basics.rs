@@ -1,8 +1,12 @@
 use gl;
 use gl::types::*;
 
+use attributes::AttributeTrait;
 use error::{GlResult, GlError};
 
+use std::marker::PhantomData;
+use std::mem;
+
 pub fn create_vao() -> GlResult<GLuint> {
     unsafe {
         let mut vao = 0;
@@ -24,3 +28,582 @@ pub fn create_buffer() -> GlResult<GLuint> {
         Ok(b)
     }
 }
+
+/// Create a VAO and declare the `buffer_layout!` type `F` in it, sourced from `vbo`.
+///
+/// Both the VAO and `vbo` (on `gl::ARRAY_BUFFER`) are left bound. The next free location is
+/// `first_location + F::count()`.
+///
+/// ```rust,no_run
+/// # #[macro_use]
+/// # extern crate rustic_gl;
+/// # fn main() {
+/// # let vbo = 0;
+/// type StaticMeshFormat = buffer_layout!([f32; 3], [f32; 2]);
+/// let vao = rustic_gl::raw::create_vao_with_layout::<StaticMeshFormat>(vbo, 0).unwrap();
+/// # }
+/// ```
+pub fn create_vao_with_layout<F: AttributeTrait>(vbo: GLuint, first_location: u32)
+    -> GlResult<GLuint>
+{
+    let vao = create_vao()?;
+    unsafe {
+        gl::BindVertexArray(vao);
+        gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+    }
+    ::attributes::declare_layout::<F>(first_location);
+    Ok(vao)
+}
+
+/// Generate `n` VAOs with a single `glGenVertexArrays` call.
+pub fn create_vaos(n: usize) -> GlResult<Vec<GLuint>> {
+    let mut vaos = vec![0; n];
+    unsafe {
+        gl::GenVertexArrays(n as GLsizei, vaos.as_mut_ptr());
+        if vaos.contains(&0) {
+            // zero names are silently ignored
+            gl::DeleteVertexArrays(n as GLsizei, vaos.as_ptr());
+            return Err(GlError::VaoCreation);
+        }
+    }
+    Ok(vaos)
+}
+
+/// Generate `n` buffers with a single `glGenBuffers` call.
+pub fn create_buffers(n: usize) -> GlResult<Vec<GLuint>> {
+    let mut buffers = vec![0; n];
+    unsafe {
+        gl::GenBuffers(n as GLsizei, buffers.as_mut_ptr());
+        if buffers.contains(&0) {
+            // zero names are silently ignored
+            gl::DeleteBuffers(n as GLsizei, buffers.as_ptr());
+            return Err(GlError::BufferCreation);
+        }
+    }
+    Ok(buffers)
+}
+
+pub fn delete_vao(id: GLuint) {
+    unsafe {
+        gl::DeleteVertexArrays(1, &id);
+    }
+}
+
+pub fn delete_vaos(ids: &[GLuint]) {
+    unsafe {
+        gl::DeleteVertexArrays(ids.len() as GLsizei, ids.as_ptr());
+    }
+}
+
+pub fn delete_buffer(id: GLuint) {
+    unsafe {
+        gl::DeleteBuffers(1, &id);
+    }
+}
+
+pub fn delete_buffers(ids: &[GLuint]) {
+    unsafe {
+        gl::DeleteBuffers(ids.len() as GLsizei, ids.as_ptr());
+    }
+}
+
+/// Create a buffer, bind it to `target`, and upload `data` with the given `usage`.
+///
+/// The buffer is left bound to `target`.
+pub fn create_buffer_with_data<T>(target: BufferTarget, data: &[T], usage: BufferUsage)
+    -> GlResult<GLuint>
+{
+    let buffer = create_buffer()?;
+    let (target, usage) = (target.to_gl_enum(), usage.to_gl_enum());
+    unsafe {
+        gl::BindBuffer(target, buffer);
+        gl::BufferData(target, mem::size_of_val(data) as GLsizeiptr, data.as_ptr() as *const _,
+                       usage);
+    }
+    Ok(buffer)
+}
+
+/// Create an element buffer from `u32` indices, returning its id and the number of indices.
+///
+/// The buffer is left bound to `gl::ELEMENT_ARRAY_BUFFER`, which is recorded in the bound VAO.
+/// Draw with `gl::UNSIGNED_INT` as the index type.
+pub fn create_index_buffer(indices: &[u32], usage: BufferUsage) -> GlResult<(GLuint, usize)> {
+    let buffer = create_buffer_with_data(BufferTarget::ElementArray, indices, usage)?;
+    Ok((buffer, indices.len()))
+}
+
+/// Like `create_index_buffer`, but for `u16` indices drawn with `gl::UNSIGNED_SHORT`.
+pub fn create_index_buffer_u16(indices: &[u16], usage: BufferUsage)
+    -> GlResult<(GLuint, usize)>
+{
+    let buffer = create_buffer_with_data(BufferTarget::ElementArray, indices, usage)?;
+    Ok((buffer, indices.len()))
+}
+
+/// Bind `buffer` to the indexed binding point `binding` of `target` (e.g. `gl::UNIFORM_BUFFER`).
+pub fn bind_buffer_base(target: GLenum, binding: u32, buffer: GLuint) {
+    unsafe {
+        gl::BindBufferBase(target, binding, buffer);
+    }
+}
+
+/// Create a buffer with immutable storage via `glBufferStorage`, leaving it bound to `target`.
+///
+/// With `Some(data)` the storage is sized to and initialized from `data`. With `None`,
+/// `byte_len` bytes of uninitialized storage are allocated instead; `byte_len` is ignored
+/// otherwise. `flags` are the storage flags, e.g. `gl::MAP_WRITE_BIT | gl::MAP_PERSISTENT_BIT`.
+pub fn create_buffer_storage<T>(target: BufferTarget, data: Option<&[T]>, byte_len: usize,
+                                flags: GLbitfield) -> GlResult<GLuint> {
+    let target = target.to_gl_enum();
+    let (size, ptr) = match data {
+        Some(data) => (mem::size_of_val(data), data.as_ptr() as *const _),
+        None => (byte_len, ::std::ptr::null()),
+    };
+    let buffer = create_buffer()?;
+    unsafe {
+        gl::BindBuffer(target, buffer);
+        gl::BufferStorage(target, size as GLsizeiptr, ptr, flags);
+    }
+    Ok(buffer)
+}
+
+/// Map `len` elements of the buffer bound to `target`, starting `byte_offset` bytes in, and
+/// return them as a slice.
+///
+/// Returns `GlError::GL_INVALID_OPERATION` if GL could not map the range, and
+/// `GlError::GL_INVALID_VALUE` (after unmapping) if the mapping is not aligned for `T`.
+///
+/// # Safety
+///
+/// The slice is only valid until the buffer is unmapped, deleted, or (without
+/// `gl::MAP_PERSISTENT_BIT`) used by GL, but its lifetime is not tied to any of that. `access`
+/// must allow the kind of access performed through the slice, and the mapped bytes must be
+/// valid values of `T`.
+pub unsafe fn map_buffer_range_mut<'a, T>(target: GLenum, byte_offset: isize, len: usize,
+                                          access: GLbitfield) -> GlResult<&'a mut [T]> {
+    let byte_len = len * mem::size_of::<T>();
+    let ptr = gl::MapBufferRange(target, byte_offset as GLintptr, byte_len as GLsizeiptr,
+                                 access) as *mut T;
+    if ptr.is_null() {
+        return Err(GlError::GL_INVALID_OPERATION);
+    }
+    if (ptr as usize) % mem::align_of::<T>() != 0 {
+        unmap_buffer(target);
+        return Err(GlError::GL_INVALID_VALUE);
+    }
+    Ok(::std::slice::from_raw_parts_mut(ptr, len))
+}
+
+/// Unmap the buffer bound to `target`.
+///
+/// Returns false if the buffer contents were corrupted while mapped (e.g. by a display mode
+/// change) and must be uploaded again.
+pub fn unmap_buffer(target: GLenum) -> bool {
+    unsafe { gl::UnmapBuffer(target) == gl::TRUE }
+}
+
+/// Create a buffer with `glCreateBuffers` (OpenGL 4.5).
+///
+/// Unlike `create_buffer`, the buffer object exists immediately, so it can be used with the
+/// direct state access functions without being bound first.
+pub fn create_named_buffer() -> GlResult<GLuint> {
+    unsafe {
+        let mut b = 0;
+        gl::CreateBuffers(1, &mut b);
+        if b == 0 {
+            return Err(GlError::BufferCreation);
+        }
+        Ok(b)
+    }
+}
+
+/// Upload `data` to `buffer` with `glNamedBufferData`, without binding it.
+pub fn named_buffer_data<T>(buffer: GLuint, data: &[T], usage: BufferUsage) {
+    unsafe {
+        gl::NamedBufferData(buffer, mem::size_of_val(data) as GLsizeiptr,
+                            data.as_ptr() as *const _, usage.to_gl_enum());
+    }
+}
+
+/// Read `count` elements of `T` from `buffer`, starting `byte_offset` bytes in.
+///
+/// The buffer is bound to `gl::COPY_READ_BUFFER` to avoid disturbing other bindings. Returns
+/// `GlError::GL_INVALID_VALUE` if the range does not fit in the buffer.
+///
+/// # Safety
+///
+/// The bytes read back must be valid values of `T`. Plain numeric types such as `f32` or
+/// `[u16; 4]` accept any bit pattern; types like `bool`, `char` or enums do not.
+pub unsafe fn read_buffer_data<T: Copy>(buffer: GLuint, byte_offset: isize, count: usize)
+    -> GlResult<Vec<T>>
+{
+    let byte_len = count * mem::size_of::<T>();
+    let mut data = Vec::<T>::with_capacity(count);
+    gl::BindBuffer(gl::COPY_READ_BUFFER, buffer);
+    check_buffer_range(gl::COPY_READ_BUFFER, byte_offset, byte_len)?;
+    gl::GetBufferSubData(gl::COPY_READ_BUFFER, byte_offset as GLintptr,
+                         byte_len as GLsizeiptr, data.as_mut_ptr() as *mut _);
+    data.set_len(count);
+    Ok(data)
+}
+
+/// Overwrite part of `buffer`, starting `byte_offset` bytes in, with `data`.
+///
+/// The buffer is bound to `gl::COPY_WRITE_BUFFER` to avoid disturbing other bindings. Returns
+/// `GlError::GL_INVALID_VALUE` if `data` does not fit in the buffer.
+pub fn update_buffer_data<T>(buffer: GLuint, byte_offset: isize, data: &[T]) -> GlResult<()> {
+    let byte_len = mem::size_of_val(data);
+    unsafe {
+        gl::BindBuffer(gl::COPY_WRITE_BUFFER, buffer);
+        check_buffer_range(gl::COPY_WRITE_BUFFER, byte_offset, byte_len)?;
+        gl::BufferSubData(gl::COPY_WRITE_BUFFER, byte_offset as GLintptr,
+                          byte_len as GLsizeiptr, data.as_ptr() as *const _);
+    }
+    Ok(())
+}
+
+/// Check that `byte_len` bytes at `byte_offset` fit in the buffer bound to `target`.
+fn check_buffer_range(target: GLenum, byte_offset: isize, byte_len: usize) -> GlResult<()> {
+    let mut size: GLint64 = 0;
+    unsafe {
+        gl::GetBufferParameteri64v(target, gl::BUFFER_SIZE, &mut size);
+    }
+    if byte_offset < 0 || byte_offset as i64 + byte_len as i64 > size {
+        return Err(GlError::GL_INVALID_VALUE);
+    }
+    Ok(())
+}
+
+pub fn clear(mask: GLbitfield) {
+    unsafe {
+        gl::Clear(mask);
+    }
+}
+
+pub fn set_clear_color(color: [f32; 4]) {
+    unsafe {
+        gl::ClearColor(color[0], color[1], color[2], color[3]);
+    }
+}
+
+/// Set the clear color and depth, then clear both the color and depth buffers.
+pub fn clear_color_depth(r: f32, g: f32, b: f32, a: f32, depth: f64) {
+    set_clear_color([r, g, b, a]);
+    unsafe {
+        gl::ClearDepth(depth);
+    }
+    clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+}
+
+pub fn set_viewport(x: i32, y: i32, width: u32, height: u32) {
+    unsafe {
+        gl::Viewport(x, y, width as GLsizei, height as GLsizei);
+    }
+}
+
+/// Set the scissor box. It only has an effect while enabled with `enable_scissor`.
+pub fn set_scissor(x: i32, y: i32, width: u32, height: u32) {
+    unsafe {
+        gl::Scissor(x, y, width as GLsizei, height as GLsizei);
+    }
+}
+
+pub fn enable_scissor(enabled: bool) {
+    unsafe {
+        if enabled {
+            gl::Enable(gl::SCISSOR_TEST);
+        } else {
+            gl::Disable(gl::SCISSOR_TEST);
+        }
+    }
+}
+
+/// Draw `instance_count` instances of `count` vertices starting at `first`.
+pub fn draw_arrays_instanced(mode: GLenum, first: i32, count: u32, instance_count: u32) {
+    unsafe {
+        gl::DrawArraysInstanced(mode, first, count as GLsizei, instance_count as GLsizei);
+    }
+}
+
+/// Draw `instance_count` instances of the first `count` indices of the bound element buffer, which
+/// holds `index_type` values (e.g. `gl::UNSIGNED_INT`).
+pub fn draw_elements_instanced(mode: GLenum, count: u32, index_type: GLenum, instance_count: u32) {
+    unsafe {
+        gl::DrawElementsInstanced(mode, count as GLsizei, index_type, ::std::ptr::null(),
+                                  instance_count as GLsizei);
+    }
+}
+
+/// The command read by `glDrawArraysIndirect`, laid out as GL expects it in the buffer.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DrawArraysIndirectCommand {
+    pub count: u32,
+    pub instance_count: u32,
+    pub first: u32,
+    pub base_instance: u32,
+}
+
+/// The command read by `glDrawElementsIndirect`, laid out as GL expects it in the buffer.
+///
+/// `first_index` is counted in indices, not bytes.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DrawElementsIndirectCommand {
+    pub count: u32,
+    pub instance_count: u32,
+    pub first_index: u32,
+    pub base_vertex: i32,
+    pub base_instance: u32,
+}
+
+const _: () = assert!(mem::size_of::<DrawArraysIndirectCommand>() == 16);
+const _: () = assert!(mem::size_of::<DrawElementsIndirectCommand>() == 20);
+
+/// Bind `indirect_buffer` to `gl::DRAW_INDIRECT_BUFFER` and draw with the
+/// `DrawArraysIndirectCommand` at `byte_offset` in it.
+pub fn draw_arrays_indirect(mode: GLenum, indirect_buffer: GLuint, byte_offset: usize) {
+    unsafe {
+        gl::BindBuffer(gl::DRAW_INDIRECT_BUFFER, indirect_buffer);
+        gl::DrawArraysIndirect(mode, byte_offset as *const _);
+    }
+}
+
+/// Bind `indirect_buffer` to `gl::DRAW_INDIRECT_BUFFER` and draw with the
+/// `DrawElementsIndirectCommand` at `byte_offset` in it, reading `index_type` values from the
+/// bound element buffer.
+pub fn draw_elements_indirect(mode: GLenum, index_type: GLenum, indirect_buffer: GLuint,
+                              byte_offset: usize)
+{
+    unsafe {
+        gl::BindBuffer(gl::DRAW_INDIRECT_BUFFER, indirect_buffer);
+        gl::DrawElementsIndirect(mode, index_type, byte_offset as *const _);
+    }
+}
+
+/// Enable primitive restart, so that `index` in an element buffer starts a new strip or fan.
+///
+/// `index` must be representable in the index buffer's type, typically its maximum value
+/// (`0xFFFF` for `u16` indices, `0xFFFF_FFFF` for `u32`); otherwise it never matches.
+pub fn enable_primitive_restart(index: GLuint) {
+    unsafe {
+        gl::Enable(gl::PRIMITIVE_RESTART);
+        gl::PrimitiveRestartIndex(index);
+    }
+}
+
+pub fn disable_primitive_restart() {
+    unsafe {
+        gl::Disable(gl::PRIMITIVE_RESTART);
+    }
+}
+
+/// The number of vertex attribute locations the context supports (`GL_MAX_VERTEX_ATTRIBS`),
+/// at least 16.
+pub fn max_vertex_attribs() -> u32 {
+    let mut max = 0;
+    unsafe {
+        gl::GetIntegerv(gl::MAX_VERTEX_ATTRIBS, &mut max);
+    }
+    max as u32
+}
+
+/// Binds a VAO for as long as the guard lives, then restores the previously bound VAO.
+#[must_use = "the previous VAO is restored as soon as the guard is dropped"]
+pub struct BoundVao {
+    previous: GLuint,
+}
+
+impl BoundVao {
+    pub fn new(vao: GLuint) -> BoundVao {
+        let previous = get_binding(gl::VERTEX_ARRAY_BINDING);
+        unsafe {
+            gl::BindVertexArray(vao);
+        }
+        BoundVao { previous }
+    }
+}
+
+impl Drop for BoundVao {
+    fn drop(&mut self) {
+        unsafe {
+            gl::BindVertexArray(self.previous);
+        }
+    }
+}
+
+/// Binds a buffer to `target` for as long as the guard lives, then restores the previous
+/// binding of `target`.
+///
+/// Note that the `gl::ELEMENT_ARRAY_BUFFER` binding is part of the bound VAO's state. For
+/// targets whose binding can't be queried, 0 is restored.
+#[must_use = "the previous buffer is restored as soon as the guard is dropped"]
+pub struct BoundBuffer {
+    target: GLenum,
+    previous: GLuint,
+}
+
+impl BoundBuffer {
+    pub fn new(target: GLenum, buffer: GLuint) -> BoundBuffer {
+        let previous = buffer_binding_query(target).map(get_binding).unwrap_or(0);
+        unsafe {
+            gl::BindBuffer(target, buffer);
+        }
+        BoundBuffer { target, previous }
+    }
+}
+
+impl Drop for BoundBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            gl::BindBuffer(self.target, self.previous);
+        }
+    }
+}
+
+fn get_binding(query: GLenum) -> GLuint {
+    let mut binding = 0;
+    unsafe {
+        gl::GetIntegerv(query, &mut binding);
+    }
+    binding as GLuint
+}
+
+/// The `glGetIntegerv` query for the buffer currently bound to `target`.
+fn buffer_binding_query(target: GLenum) -> Option<GLenum> {
+    Some(match target {
+        gl::ARRAY_BUFFER => gl::ARRAY_BUFFER_BINDING,
+        gl::ELEMENT_ARRAY_BUFFER => gl::ELEMENT_ARRAY_BUFFER_BINDING,
+        gl::UNIFORM_BUFFER => gl::UNIFORM_BUFFER_BINDING,
+        gl::SHADER_STORAGE_BUFFER => gl::SHADER_STORAGE_BUFFER_BINDING,
+        gl::TRANSFORM_FEEDBACK_BUFFER => gl::TRANSFORM_FEEDBACK_BUFFER_BINDING,
+        gl::COPY_READ_BUFFER => gl::COPY_READ_BUFFER_BINDING,
+        gl::COPY_WRITE_BUFFER => gl::COPY_WRITE_BUFFER_BINDING,
+        gl::PIXEL_PACK_BUFFER => gl::PIXEL_PACK_BUFFER_BINDING,
+        gl::PIXEL_UNPACK_BUFFER => gl::PIXEL_UNPACK_BUFFER_BINDING,
+        gl::DRAW_INDIRECT_BUFFER => gl::DRAW_INDIRECT_BUFFER_BINDING,
+        gl::DISPATCH_INDIRECT_BUFFER => gl::DISPATCH_INDIRECT_BUFFER_BINDING,
+        gl::ATOMIC_COUNTER_BUFFER => gl::ATOMIC_COUNTER_BUFFER_BINDING,
+        gl::QUERY_BUFFER => gl::QUERY_BUFFER_BINDING,
+        gl::TEXTURE_BUFFER => gl::TEXTURE_BUFFER_BINDING,
+        _ => return None,
+    })
+}
+
+/// A buffer binding target, for `Buffer` and the data-upload helpers such as
+/// `create_buffer_with_data`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BufferTarget {
+    Array,
+    ElementArray,
+    Uniform,
+    ShaderStorage,
+    CopyRead,
+    CopyWrite,
+    PixelPack,
+    PixelUnpack,
+    DrawIndirect,
+    DispatchIndirect,
+    TransformFeedback,
+    AtomicCounter,
+    Texture,
+}
+
+impl BufferTarget {
+    pub fn to_gl_enum(self) -> GLenum {
+        match self {
+            BufferTarget::Array => gl::ARRAY_BUFFER,
+            BufferTarget::ElementArray => gl::ELEMENT_ARRAY_BUFFER,
+            BufferTarget::Uniform => gl::UNIFORM_BUFFER,
+            BufferTarget::ShaderStorage => gl::SHADER_STORAGE_BUFFER,
+            BufferTarget::CopyRead => gl::COPY_READ_BUFFER,
+            BufferTarget::CopyWrite => gl::COPY_WRITE_BUFFER,
+            BufferTarget::PixelPack => gl::PIXEL_PACK_BUFFER,
+            BufferTarget::PixelUnpack => gl::PIXEL_UNPACK_BUFFER,
+            BufferTarget::DrawIndirect => gl::DRAW_INDIRECT_BUFFER,
+            BufferTarget::DispatchIndirect => gl::DISPATCH_INDIRECT_BUFFER,
+            BufferTarget::TransformFeedback => gl::TRANSFORM_FEEDBACK_BUFFER,
+            BufferTarget::AtomicCounter => gl::ATOMIC_COUNTER_BUFFER,
+            BufferTarget::Texture => gl::TEXTURE_BUFFER,
+        }
+    }
+}
+
+/// A `glBufferData` usage hint, for `Buffer` and the data-upload helpers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BufferUsage {
+    StaticDraw,
+    DynamicDraw,
+    StreamDraw,
+    StaticRead,
+    DynamicRead,
+    StreamRead,
+    StaticCopy,
+    DynamicCopy,
+    StreamCopy,
+}
+
+impl BufferUsage {
+    pub fn to_gl_enum(self) -> GLenum {
+        match self {
+            BufferUsage::StaticDraw => gl::STATIC_DRAW,
+            BufferUsage::DynamicDraw => gl::DYNAMIC_DRAW,
+            BufferUsage::StreamDraw => gl::STREAM_DRAW,
+            BufferUsage::StaticRead => gl::STATIC_READ,
+            BufferUsage::DynamicRead => gl::DYNAMIC_READ,
+            BufferUsage::StreamRead => gl::STREAM_READ,
+            BufferUsage::StaticCopy => gl::STATIC_COPY,
+            BufferUsage::DynamicCopy => gl::DYNAMIC_COPY,
+            BufferUsage::StreamCopy => gl::STREAM_COPY,
+        }
+    }
+}
+
+/// An optional typed layer over a buffer id, which remembers the element type and length it was
+/// created with and deletes the buffer when dropped.
+///
+/// Everything here can also be done with the plain functions above; use `id()` to pass the buffer
+/// to them.
+pub struct Buffer<T> {
+    id: GLuint,
+    len: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T> Buffer<T> {
+    /// Create a buffer holding `data`, as `create_buffer_with_data`. The buffer is left bound to
+    /// `target`.
+    pub fn new(data: &[T], target: BufferTarget, usage: BufferUsage) -> GlResult<Buffer<T>> {
+        let id = create_buffer_with_data(target, data, usage)?;
+        Ok(Buffer { id, len: data.len(), _marker: PhantomData })
+    }
+
+    pub fn id(&self) -> GLuint {
+        self.id
+    }
+
+    /// The number of `T`s the buffer holds.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Overwrite the elements starting at index `offset` with `data`.
+    ///
+    /// Returns `GlError::GL_INVALID_VALUE` without touching GL if `data` does not fit.
+    pub fn update(&self, offset: usize, data: &[T]) -> GlResult<()> {
+        if offset + data.len() > self.len {
+            return Err(GlError::GL_INVALID_VALUE);
+        }
+        update_buffer_data(self.id, (offset * mem::size_of::<T>()) as isize, data)
+    }
+}
+
+impl<T> Drop for Buffer<T> {
+    fn drop(&mut self) {
+        delete_buffer(self.id);
+    }
+}
@@ -0,0 +1,114 @@
+//! Thin wrappers over framebuffer objects. The framebuffer is left bound to
+//! `gl::FRAMEBUFFER` by every function that takes one.
+
+use gl;
+use gl::types::*;
+
+use error::{GlResult, GlError};
+
+pub fn create_framebuffer() -> GlResult<GLuint> {
+    unsafe {
+        let mut fbo = 0;
+        gl::GenFramebuffers(1, &mut fbo);
+        if fbo == 0 {
+            return Err(GlError::FramebufferCreation);
+        }
+        Ok(fbo)
+    }
+}
+
+pub fn delete_framebuffer(fbo: GLuint) {
+    unsafe {
+        gl::DeleteFramebuffers(1, &fbo);
+    }
+}
+
+/// Attach level 0 of `tex` to `attachment` (e.g. `gl::COLOR_ATTACHMENT0`) of `fbo`.
+pub fn attach_texture(fbo: GLuint, attachment: GLenum, tex: GLuint) {
+    unsafe {
+        gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+        gl::FramebufferTexture(gl::FRAMEBUFFER, attachment, tex, 0);
+    }
+}
+
+/// Check that `fbo` can be rendered to.
+///
+/// Returns `GlError::FramebufferIncomplete` with the status GL reported otherwise.
+pub fn check_framebuffer_complete(fbo: GLuint) -> GlResult<()> {
+    let status = unsafe {
+        gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+        gl::CheckFramebufferStatus(gl::FRAMEBUFFER)
+    };
+    if status != gl::FRAMEBUFFER_COMPLETE {
+        return Err(GlError::FramebufferIncomplete(status));
+    }
+    Ok(())
+}
+
+/// Create a renderbuffer with storage for `width` x `height` pixels of `internal_format`, e.g.
+/// `gl::DEPTH24_STENCIL8`. The renderbuffer is left bound.
+pub fn create_renderbuffer(internal_format: GLenum, width: u32, height: u32) -> GlResult<GLuint> {
+    unsafe {
+        let mut rbo = 0;
+        gl::GenRenderbuffers(1, &mut rbo);
+        if rbo == 0 {
+            return Err(GlError::RenderbufferCreation);
+        }
+        gl::BindRenderbuffer(gl::RENDERBUFFER, rbo);
+        gl::RenderbufferStorage(gl::RENDERBUFFER, internal_format, width as GLsizei,
+                                height as GLsizei);
+        Ok(rbo)
+    }
+}
+
+pub fn delete_renderbuffer(rbo: GLuint) {
+    unsafe {
+        gl::DeleteRenderbuffers(1, &rbo);
+    }
+}
+
+/// Attach `rbo` to `attachment` (e.g. `gl::DEPTH_STENCIL_ATTACHMENT`) of `fbo`.
+pub fn attach_renderbuffer(fbo: GLuint, attachment: GLenum, rbo: GLuint) {
+    unsafe {
+        gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+        gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, attachment, gl::RENDERBUFFER, rbo);
+    }
+}
+
+/// Select the color attachments of the bound draw framebuffer that fragment outputs
+/// `0..attachments.len()` are written to.
+///
+/// Pair this with `create_linked_program_with_frag_data` to route named outputs.
+pub fn set_draw_buffers(attachments: &[GLenum]) {
+    unsafe {
+        gl::DrawBuffers(attachments.len() as GLsizei, attachments.as_ptr());
+    }
+}
+
+/// Route fragment output `i` to `gl::COLOR_ATTACHMENT0 + i`, for `i` in `0..n`.
+pub fn set_draw_buffers_n(n: u32) {
+    let attachments: Vec<GLenum> = (0..n).map(|i| gl::COLOR_ATTACHMENT0 + i).collect();
+    set_draw_buffers(&attachments);
+}
+
+/// Copy `src_rect` of `src` into `dst_rect` of `dst`, e.g. to resolve a multisampled
+/// framebuffer. Rects are `(x0, y0, x1, y1)`; 0 names the default framebuffer.
+///
+/// Unlike the rest of this module, the read and draw framebuffer bindings are restored
+/// afterward.
+pub fn blit_framebuffer(src: GLuint, dst: GLuint, src_rect: (i32, i32, i32, i32),
+                        dst_rect: (i32, i32, i32, i32), mask: GLbitfield, filter: GLenum)
+{
+    let (mut previous_read, mut previous_draw) = (0, 0);
+    unsafe {
+        gl::GetIntegerv(gl::READ_FRAMEBUFFER_BINDING, &mut previous_read);
+        gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut previous_draw);
+        gl::BindFramebuffer(gl::READ_FRAMEBUFFER, src);
+        gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, dst);
+        gl::BlitFramebuffer(src_rect.0, src_rect.1, src_rect.2, src_rect.3,
+                            dst_rect.0, dst_rect.1, dst_rect.2, dst_rect.3,
+                            mask, filter);
+        gl::BindFramebuffer(gl::READ_FRAMEBUFFER, previous_read as GLuint);
+        gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, previous_draw as GLuint);
+    }
+}
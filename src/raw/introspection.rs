@@ -0,0 +1,127 @@
+use gl;
+use gl::types::*;
+
+use error::GlResult;
+use super::shaders::get_link_status;
+
+use std::collections::HashMap;
+
+/// Query every active uniform of a linked program.
+///
+/// Maps each uniform name to its `(location, type, array size)`. Array uniforms are reported by
+/// GL as `name[0]`; these are normalized to the base `name`. Uniforms inside a uniform block have
+/// a location of `-1`.
+pub fn get_active_uniforms(program: GLuint) -> GlResult<HashMap<String, (GLint, GLenum, GLint)>> {
+    get_link_status(program)?;
+    let mut uniforms = HashMap::new();
+    unsafe {
+        let mut count = 0;
+        gl::GetProgramiv(program, gl::ACTIVE_UNIFORMS, &mut count);
+        let mut max_length = 0;
+        gl::GetProgramiv(program, gl::ACTIVE_UNIFORM_MAX_LENGTH, &mut max_length);
+
+        for i in 0..count as GLuint {
+            let mut name = vec![0u8; max_length as usize];
+            let mut length = 0;
+            let mut size = 0;
+            let mut kind = 0;
+            gl::GetActiveUniform(program, i, max_length, &mut length, &mut size, &mut kind,
+                name.as_mut_ptr() as *mut GLchar);
+            // GL null terminated the name for us, so it can be passed straight back in
+            let location = gl::GetUniformLocation(program, name.as_ptr() as *const GLchar);
+
+            let mut name = read_name(name, length);
+            if name.ends_with("[0]") {
+                let base = name.len() - 3;
+                name.truncate(base);
+            }
+            uniforms.insert(name, (location, kind, size));
+        }
+    }
+    Ok(uniforms)
+}
+
+/// Query every active vertex attribute (`in` variable) of a linked program.
+///
+/// Returns `(name, location, type)` for each attribute, where `type` is e.g. `gl::FLOAT_VEC3`.
+/// Built-in attributes such as `gl_VertexID` have no location and are skipped.
+pub fn get_active_attributes(program: GLuint) -> GlResult<Vec<(String, u32, GLenum)>> {
+    get_link_status(program)?;
+    let mut attributes = Vec::new();
+    unsafe {
+        let mut count = 0;
+        gl::GetProgramiv(program, gl::ACTIVE_ATTRIBUTES, &mut count);
+        let mut max_length = 0;
+        gl::GetProgramiv(program, gl::ACTIVE_ATTRIBUTE_MAX_LENGTH, &mut max_length);
+
+        for i in 0..count as GLuint {
+            let mut name = vec![0u8; max_length as usize];
+            let mut length = 0;
+            let mut size = 0;
+            let mut kind = 0;
+            gl::GetActiveAttrib(program, i, max_length, &mut length, &mut size, &mut kind,
+                name.as_mut_ptr() as *mut GLchar);
+            let location = gl::GetAttribLocation(program, name.as_ptr() as *const GLchar);
+            if location < 0 {
+                continue;
+            }
+            attributes.push((read_name(name, length), location as u32, kind));
+        }
+    }
+    Ok(attributes)
+}
+
+/// An active resource of a program interface, as reported by `get_program_resources`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramResource {
+    /// Array resources keep GL's `[0]` suffix.
+    pub name: String,
+    /// -1 for resources without a location, such as built-ins and uniforms inside a block.
+    pub location: GLint,
+    /// The GLSL type, e.g. `gl::FLOAT_VEC3`.
+    pub kind: GLenum,
+    /// 1 for resources that are not arrays.
+    pub array_size: GLint,
+}
+
+/// Query every active resource of `interface` in a linked program (OpenGL 4.3).
+///
+/// Supported interfaces are those whose resources have locations: `gl::PROGRAM_INPUT`,
+/// `gl::PROGRAM_OUTPUT`, and `gl::UNIFORM`.
+pub fn get_program_resources(program: GLuint, interface: GLenum)
+    -> GlResult<Vec<ProgramResource>>
+{
+    get_link_status(program)?;
+    let mut resources = Vec::new();
+    unsafe {
+        let mut count = 0;
+        gl::GetProgramInterfaceiv(program, interface, gl::ACTIVE_RESOURCES, &mut count);
+        let mut max_length = 0;
+        gl::GetProgramInterfaceiv(program, interface, gl::MAX_NAME_LENGTH, &mut max_length);
+
+        let properties = [gl::TYPE, gl::ARRAY_SIZE, gl::LOCATION];
+        for i in 0..count as GLuint {
+            let mut name = vec![0u8; max_length as usize];
+            let mut length = 0;
+            gl::GetProgramResourceName(program, interface, i, max_length, &mut length,
+                name.as_mut_ptr() as *mut GLchar);
+            let mut values = [0; 3];
+            gl::GetProgramResourceiv(program, interface, i, properties.len() as GLsizei,
+                properties.as_ptr(), values.len() as GLsizei, ::std::ptr::null_mut(),
+                values.as_mut_ptr());
+            resources.push(ProgramResource {
+                name: read_name(name, length),
+                location: values[2],
+                kind: values[0] as GLenum,
+                array_size: values[1],
+            });
+        }
+    }
+    Ok(resources)
+}
+
+/// Convert a name buffer filled in by GL into a `String`, given the length GL reported.
+fn read_name(mut raw: Vec<u8>, length: GLsizei) -> String {
+    raw.truncate(length as usize);
+    String::from_utf8_lossy(&raw).into_owned()
+}
@@ -1,8 +1,28 @@
+//! Thin wrappers over GL calls, re-exported here by function.
+//!
+//! The guards (`BoundVao`, `BoundBuffer`, `UsedProgram`) undo their change when dropped, so bind
+//! them to a named variable, e.g. `let _vao = BoundVao::new(vao);`. `let _ = ...` drops the guard
+//! immediately.
+
 pub mod shaders;
+pub mod introspection;
+pub mod uniforms;
+pub mod textures;
+pub mod framebuffers;
+pub mod samplers;
+pub mod sync;
+pub mod queries;
 // TODO: Do we want to call this the "basics" module? Better name? Don't export till resolved.
 mod basics;
 
 // Re-export everything for people who do not want to refer to the individual modules
 
 pub use self::shaders::*;
+pub use self::introspection::*;
+pub use self::uniforms::*;
+pub use self::textures::*;
+pub use self::framebuffers::*;
+pub use self::samplers::*;
+pub use self::sync::*;
+pub use self::queries::*;
 pub use self::basics::*;
@@ -0,0 +1,60 @@
+//! Thin wrappers over query objects, e.g. `gl::TIME_ELAPSED` and `gl::SAMPLES_PASSED`.
+
+use gl;
+use gl::types::*;
+
+use error::{GlResult, GlError};
+
+pub fn create_query() -> GlResult<GLuint> {
+    unsafe {
+        let mut query = 0;
+        gl::GenQueries(1, &mut query);
+        if query == 0 {
+            return Err(GlError::QueryCreation);
+        }
+        Ok(query)
+    }
+}
+
+pub fn delete_query(id: GLuint) {
+    unsafe {
+        gl::DeleteQueries(1, &id);
+    }
+}
+
+/// Start measuring `target` (e.g. `gl::TIME_ELAPSED`) into the query `id`.
+pub fn begin_query(target: GLenum, id: GLuint) {
+    unsafe {
+        gl::BeginQuery(target, id);
+    }
+}
+
+pub fn end_query(target: GLenum) {
+    unsafe {
+        gl::EndQuery(target);
+    }
+}
+
+/// Whether the result of `id` can be read without blocking.
+pub fn is_query_available(id: GLuint) -> bool {
+    let mut available = gl::FALSE as GLuint;
+    unsafe {
+        gl::GetQueryObjectuiv(id, gl::QUERY_RESULT_AVAILABLE, &mut available);
+    }
+    available != gl::FALSE as GLuint
+}
+
+/// Read the result of `id`, blocking until it is available. Elapsed times are in nanoseconds.
+///
+/// Returns `GlError::GL_INVALID_OPERATION` if `id` has never been begun.
+pub fn get_query_result_u64(id: GLuint) -> GlResult<u64> {
+    let mut result = 0;
+    unsafe {
+        // names from glGenQueries only become query objects once begun
+        if gl::IsQuery(id) == gl::FALSE {
+            return Err(GlError::GL_INVALID_OPERATION);
+        }
+        gl::GetQueryObjectui64v(id, gl::QUERY_RESULT, &mut result);
+    }
+    Ok(result)
+}
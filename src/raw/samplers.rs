@@ -0,0 +1,44 @@
+//! Thin wrappers over sampler objects, which override the sampling state of whatever texture is
+//! bound to the same unit.
+
+use gl;
+use gl::types::*;
+
+use error::{GlResult, GlError};
+use super::textures::TextureParams;
+
+pub fn create_sampler() -> GlResult<GLuint> {
+    unsafe {
+        let mut sampler = 0;
+        gl::GenSamplers(1, &mut sampler);
+        if sampler == 0 {
+            return Err(GlError::SamplerCreation);
+        }
+        Ok(sampler)
+    }
+}
+
+pub fn delete_sampler(sampler: GLuint) {
+    unsafe {
+        gl::DeleteSamplers(1, &sampler);
+    }
+}
+
+/// Set the filtering and wrap modes of `sampler`. Unlike texture parameters, this doesn't
+/// require anything to be bound.
+pub fn set_sampler_parameters(sampler: GLuint, params: &TextureParams) {
+    unsafe {
+        gl::SamplerParameteri(sampler, gl::TEXTURE_MIN_FILTER, params.min_filter as GLint);
+        gl::SamplerParameteri(sampler, gl::TEXTURE_MAG_FILTER, params.mag_filter as GLint);
+        gl::SamplerParameteri(sampler, gl::TEXTURE_WRAP_S, params.wrap_s as GLint);
+        gl::SamplerParameteri(sampler, gl::TEXTURE_WRAP_T, params.wrap_t as GLint);
+    }
+}
+
+/// Bind `sampler` to texture unit `unit` (an index, not `gl::TEXTURE0 + unit`). Binding 0
+/// reverts the unit to the texture's own parameters.
+pub fn bind_sampler(unit: u32, sampler: GLuint) {
+    unsafe {
+        gl::BindSampler(unit, sampler);
+    }
+}
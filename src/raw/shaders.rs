@@ -3,6 +3,14 @@ use gl::types::*;
 
 use error::{GlResult, GlError};
 
+use std::ffi::CString;
+use std::fs;
+use std::mem;
+use std::os::raw::c_void;
+use std::path::Path;
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
 macro_rules! get_info_log {
     ($get_attr:path, $get_log:path, $gl_id:expr) => {{
         let mut log_length_glint: GLint = 0;
@@ -11,12 +19,14 @@ macro_rules! get_info_log {
         if log_length == 0 {
             None
         } else {
-            let mut raw_log = Vec::<u8>::with_capacity(log_length);
+            let mut raw_log = vec![0u8; log_length];
+            // the reported length excludes the null terminator GL writes into the buffer
+            let mut written: GLsizei = 0;
             $get_log($gl_id, log_length as GLsizei,
-                0 as *mut GLsizei, raw_log.as_mut_ptr() as *mut GLchar);
-            raw_log.set_len(log_length);
-            let log = String::from_utf8(raw_log)
-                .expect("OpenGL returned invalid utf8 in a program info log");
+                &mut written, raw_log.as_mut_ptr() as *mut GLchar);
+            raw_log.truncate(written as usize);
+            // drivers are not guaranteed to emit utf8, and a garbled log beats a panic here
+            let log = String::from_utf8_lossy(&raw_log).into_owned();
             Some(log)
         }
     }}
@@ -31,12 +41,100 @@ pub fn create_program() -> GlResult<GLuint> {
     }
 }
 
-pub fn create_shader(kind: GLenum, source: &str) -> GlResult<GLuint> {
+pub fn delete_program(id: GLuint) {
     unsafe {
-        let gl_id = gl::CreateShader(kind as _);
-        if gl_id == 0 {
-            return Err(GlError::ShaderCreation);
+        gl::DeleteProgram(id);
+    }
+}
+
+/// Uses a program for as long as the guard lives, then restores the previously used program.
+#[must_use = "the previous program is restored as soon as the guard is dropped"]
+pub struct UsedProgram {
+    previous: GLuint,
+}
+
+impl UsedProgram {
+    pub fn new(program: GLuint) -> UsedProgram {
+        let mut previous = 0;
+        unsafe {
+            gl::GetIntegerv(gl::CURRENT_PROGRAM, &mut previous);
+            gl::UseProgram(program);
+        }
+        UsedProgram { previous: previous as GLuint }
+    }
+}
+
+impl Drop for UsedProgram {
+    fn drop(&mut self) {
+        unsafe {
+            gl::UseProgram(self.previous);
+        }
+    }
+}
+
+/// The programmable stages a shader can be created for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShaderKind {
+    Vertex,
+    Fragment,
+    Geometry,
+    TessControl,
+    TessEval,
+    Compute,
+}
+
+impl ShaderKind {
+    pub fn to_gl_enum(self) -> GLenum {
+        match self {
+            ShaderKind::Vertex => gl::VERTEX_SHADER,
+            ShaderKind::Fragment => gl::FRAGMENT_SHADER,
+            ShaderKind::Geometry => gl::GEOMETRY_SHADER,
+            ShaderKind::TessControl => gl::TESS_CONTROL_SHADER,
+            ShaderKind::TessEval => gl::TESS_EVALUATION_SHADER,
+            ShaderKind::Compute => gl::COMPUTE_SHADER,
         }
+    }
+}
+
+/// Like `create_shader`, but takes a `ShaderKind` rather than an arbitrary `GLenum`.
+pub fn create_shader_of_kind(kind: ShaderKind, source: &str) -> GlResult<GLuint> {
+    create_shader(kind.to_gl_enum(), source)
+}
+
+/// Create and compile a shader of the given `kind` (e.g. `gl::VERTEX_SHADER`).
+///
+/// Any stage supported by the context may be passed, including `gl::GEOMETRY_SHADER`,
+/// `gl::TESS_CONTROL_SHADER`, `gl::TESS_EVALUATION_SHADER`, and `gl::COMPUTE_SHADER`. The
+/// resulting id can be handed to `create_linked_program` along with the other stages.
+///
+/// Returns `GlError::ShaderCompilation` with the info log if compilation fails.
+pub fn create_shader(kind: GLenum, source: &str) -> GlResult<GLuint> {
+    let gl_id = start_shader_compile(kind, source)?;
+    get_compile_status(gl_id)?;
+    Ok(gl_id)
+}
+
+/// Like `create_shader`, but also returns the info log when compilation succeeds.
+///
+/// Compilers often report warnings (deprecations, performance hints) in the log of a successful
+/// compile; the log is `None` if it is empty.
+pub fn create_shader_verbose(kind: GLenum, source: &str) -> GlResult<(GLuint, Option<String>)> {
+    let gl_id = start_shader_compile(kind, source)?;
+    get_compile_status(gl_id)?;
+    let log = unsafe { get_info_log!(gl::GetShaderiv, gl::GetShaderInfoLog, gl_id) };
+    Ok((gl_id, log.filter(|log| !log.trim().is_empty())))
+}
+
+/// Create a shader and start compiling `source`, without waiting for or checking the result.
+///
+/// With `KHR_parallel_shader_compile` the compile may happen in the background: poll
+/// `is_shader_compiled`, then call `get_compile_status` once it returns true.
+pub fn start_shader_compile(kind: GLenum, source: &str) -> GlResult<GLuint> {
+    let gl_id = unsafe { gl::CreateShader(kind as _) };
+    if gl_id == 0 {
+        return Err(GlError::ShaderCreation);
+    }
+    unsafe {
         gl::ShaderSource(
             gl_id,
             1,
@@ -44,19 +142,259 @@ pub fn create_shader(kind: GLenum, source: &str) -> GlResult<GLuint> {
             &(source.len() as _)
         );
         gl::CompileShader(gl_id);
+    }
+    Ok(gl_id)
+}
 
-        let mut status = 0;
-        gl::GetShaderiv(gl_id, gl::COMPILE_STATUS, &mut status);
+pub fn get_compile_status(shader_id: GLuint) -> GlResult<()> {
+    let mut status = 0;
+    unsafe {
+        gl::GetShaderiv(shader_id, gl::COMPILE_STATUS, &mut status);
         if status != 1 {
             Err(GlError::ShaderCompilation(
-                get_info_log!(gl::GetShaderiv, gl::GetShaderInfoLog, gl_id)
+                get_info_log!(gl::GetShaderiv, gl::GetShaderInfoLog, shader_id)
             ))
         } else {
-            Ok(gl_id)
+            Ok(())
+        }
+    }
+}
+
+/// Read back the source GL holds for `shader_id`, e.g. to inspect the output of
+/// `create_shader_with_includes`.
+///
+/// Invalid ids are reported by GL itself; use `error::check_error` to see them.
+pub fn get_shader_source(shader_id: GLuint) -> GlResult<String> {
+    let mut length: GLint = 0;
+    unsafe {
+        gl::GetShaderiv(shader_id, gl::SHADER_SOURCE_LENGTH, &mut length);
+    }
+    if length <= 0 {
+        return Ok(String::new());
+    }
+    let mut raw_source = vec![0u8; length as usize];
+    // as with the info log, the reported length includes the null terminator but `written` doesn't
+    let mut written: GLsizei = 0;
+    unsafe {
+        gl::GetShaderSource(shader_id, length, &mut written,
+                            raw_source.as_mut_ptr() as *mut GLchar);
+    }
+    raw_source.truncate(written as usize);
+    Ok(String::from_utf8_lossy(&raw_source).into_owned())
+}
+
+/// `GL_COMPLETION_STATUS_KHR`, from `KHR_parallel_shader_compile`.
+const COMPLETION_STATUS_KHR: GLenum = 0x91B1;
+
+/// Set the number of threads the driver may use to compile shaders in the background
+/// (`KHR_parallel_shader_compile`).
+///
+/// Requires `load_extensions_with` to have been called; returns `GlError::Unsupported` if the
+/// entry point is missing.
+pub fn set_max_shader_compiler_threads(n: u32) -> GlResult<()> {
+    let f = MAX_SHADER_COMPILER_THREADS.load(Ordering::SeqCst);
+    if f == 0 {
+        return Err(GlError::Unsupported("KHR_parallel_shader_compile"));
+    }
+    unsafe {
+        let f: MaxShaderCompilerThreadsFn = mem::transmute(f);
+        f(n);
+    }
+    Ok(())
+}
+
+/// Whether `KHR_parallel_shader_compile` (or the ARB variant) was found by
+/// `load_extensions_with`. Querying `GL_COMPLETION_STATUS_KHR` without it raises
+/// `GL_INVALID_ENUM`.
+fn has_parallel_shader_compile() -> bool {
+    MAX_SHADER_COMPILER_THREADS.load(Ordering::SeqCst) != 0
+}
+
+/// Whether a compile started with `start_shader_compile` has finished, without blocking.
+///
+/// Always true if `KHR_parallel_shader_compile` is unsupported or `load_extensions_with` hasn't
+/// been called, in which case checking the status simply blocks as usual.
+pub fn is_shader_compiled(shader_id: GLuint) -> bool {
+    if !has_parallel_shader_compile() {
+        return true;
+    }
+    let mut done = gl::TRUE as GLint;
+    unsafe {
+        gl::GetShaderiv(shader_id, COMPLETION_STATUS_KHR, &mut done);
+    }
+    done != gl::FALSE as GLint
+}
+
+/// Whether a link started with `start_program_link` has finished, without blocking.
+///
+/// Always true if `KHR_parallel_shader_compile` is unsupported or `load_extensions_with` hasn't
+/// been called.
+pub fn is_program_linked(program_id: GLuint) -> bool {
+    if !has_parallel_shader_compile() {
+        return true;
+    }
+    let mut done = gl::TRUE as GLint;
+    unsafe {
+        gl::GetProgramiv(program_id, COMPLETION_STATUS_KHR, &mut done);
+    }
+    done != gl::FALSE as GLint
+}
+
+/// `GL_SHADER_BINARY_FORMAT_SPIR_V`, which is not part of the bundled GL 4.5 bindings.
+pub const SHADER_BINARY_FORMAT_SPIR_V: GLenum = 0x9551;
+
+type SpecializeShaderFn = extern "system" fn(GLuint, *const GLchar, GLuint, *const GLuint,
+                                             *const GLuint);
+type MaxShaderCompilerThreadsFn = extern "system" fn(GLuint);
+
+static SPECIALIZE_SHADER: AtomicUsize = AtomicUsize::new(0);
+static MAX_SHADER_COMPILER_THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// Load the entry points used by this module that the bundled GL 4.5 bindings lack.
+///
+/// These are `glSpecializeShader` (GL 4.6 or `ARB_gl_spirv`), needed by
+/// `create_shader_from_spirv`, and `glMaxShaderCompilerThreadsKHR`, needed by
+/// `set_max_shader_compiler_threads`. Call this with the same loader you passed to
+/// `gl::load_with`; functions whose entry point is missing return `GlError::Unsupported`.
+pub fn load_extensions_with<F>(mut loader: F)
+    where F: FnMut(&str) -> *const c_void
+{
+    load_entry_point(&SPECIALIZE_SHADER, &mut loader,
+                     &["glSpecializeShader", "glSpecializeShaderARB"]);
+    load_entry_point(&MAX_SHADER_COMPILER_THREADS, &mut loader,
+                     &["glMaxShaderCompilerThreadsKHR", "glMaxShaderCompilerThreadsARB"]);
+}
+
+/// Store the first of `names` that `loader` finds, or null if none are found.
+fn load_entry_point<F>(slot: &AtomicUsize, loader: &mut F, names: &[&str])
+    where F: FnMut(&str) -> *const c_void
+{
+    let f = names.iter()
+        .map(|name| loader(name))
+        .find(|f| !f.is_null())
+        .unwrap_or(ptr::null());
+    slot.store(f as usize, Ordering::SeqCst);
+}
+
+/// Create a shader from a SPIR-V module using `glShaderBinary` and `glSpecializeShader`.
+///
+/// Requires GL 4.6 or `ARB_gl_spirv`, and `load_extensions_with` must have been called first;
+/// otherwise `GlError::Unsupported` is returned. Specialization failures are reported as
+/// `GlError::ShaderCompilation` exactly like `create_shader`.
+pub fn create_shader_from_spirv(kind: GLenum, spirv: &[u8], entry_point: &str)
+    -> GlResult<GLuint>
+{
+    let specialize = SPECIALIZE_SHADER.load(Ordering::SeqCst);
+    if specialize == 0 {
+        return Err(GlError::Unsupported("ARB_gl_spirv"));
+    }
+    let entry_point = CString::new(entry_point).map_err(|_| {
+        GlError::ShaderCompilation(Some("entry point contains a nul byte".to_string()))
+    })?;
+    unsafe {
+        let specialize: SpecializeShaderFn = mem::transmute(specialize);
+        let gl_id = gl::CreateShader(kind);
+        if gl_id == 0 {
+            return Err(GlError::ShaderCreation);
         }
+        gl::ShaderBinary(1, &gl_id, SHADER_BINARY_FORMAT_SPIR_V, spirv.as_ptr() as *const _,
+            spirv.len() as GLsizei);
+        specialize(gl_id, entry_point.as_ptr(), 0, ptr::null(), ptr::null());
+        get_compile_status(gl_id)?;
+        Ok(gl_id)
     }
 }
 
+/// Read a shader source file as UTF-8 and compile it with `create_shader`.
+///
+/// Failing to read the file is reported as `GlError::Io`.
+pub fn create_shader_from_file(kind: GLenum, path: &Path) -> GlResult<GLuint> {
+    let source = fs::read_to_string(path)?;
+    create_shader(kind, &source)
+}
+
+/// Create a shader from the result of applying `transform` to `source`, e.g. a custom macro
+/// expansion pass.
+///
+/// Errors from `transform` are returned as-is, before any GL call is made.
+pub fn create_shader_with_transform<F>(kind: GLenum, source: &str, transform: F)
+    -> GlResult<GLuint>
+    where F: FnOnce(&str) -> GlResult<String>
+{
+    let transformed = transform(source)?;
+    create_shader(kind, &transformed)
+}
+
+/// Create a shader after recursively expanding `#include "name"` directives.
+///
+/// Each included name is passed to `resolver`, which returns the source to splice in place of the
+/// directive. Errors from the resolver are returned as-is. An include that (indirectly) includes
+/// itself is reported as `GlError::IncludeCycle` with the offending name.
+pub fn create_shader_with_includes<F>(kind: GLenum, source: &str, resolver: F) -> GlResult<GLuint>
+    where F: Fn(&str) -> GlResult<String>
+{
+    create_shader_with_transform(kind, source, |source| {
+        expand_includes(source, &resolver, &mut Vec::new())
+    })
+}
+
+/// Create a shader from `body`, prefixed with a `#version` line and a `#define` per entry of
+/// `defines`.
+///
+/// A `#line 1` directive follows the header so that line numbers in compiler errors still refer
+/// to `body`. `body` must not contain its own `#version` directive.
+pub fn create_shader_with_defines(kind: GLenum, version: &str, defines: &[(&str, &str)],
+                                  body: &str) -> GlResult<GLuint> {
+    create_shader_with_transform(kind, body, |body| Ok(with_defines(version, defines, body)))
+}
+
+fn with_defines(version: &str, defines: &[(&str, &str)], body: &str) -> String {
+    let mut source = format!("#version {}\n", version);
+    for &(name, value) in defines {
+        source.push_str(&format!("#define {} {}\n", name, value));
+    }
+    source.push_str("#line 1\n");
+    source.push_str(body);
+    source
+}
+
+fn expand_includes<F>(source: &str, resolver: &F, stack: &mut Vec<String>) -> GlResult<String>
+    where F: Fn(&str) -> GlResult<String>
+{
+    let mut out = String::with_capacity(source.len());
+    for line in source.lines() {
+        let directive = line.trim_start();
+        if !directive.starts_with("#include") {
+            out.push_str(line);
+            out.push('\n');
+            continue;
+        }
+        let name = parse_include(&directive["#include".len()..]).ok_or_else(|| {
+            GlError::ShaderCompilation(Some(format!("malformed include directive: {}", line)))
+        })?;
+        if stack.iter().any(|n| n == name) {
+            return Err(GlError::IncludeCycle(name.to_string()));
+        }
+        let included = resolver(name)?;
+        stack.push(name.to_string());
+        out.push_str(&expand_includes(&included, resolver, stack)?);
+        stack.pop();
+    }
+    Ok(out)
+}
+
+/// Extract `name` from the remainder of an `#include "name"` or `#include <name>` line.
+fn parse_include(rest: &str) -> Option<&str> {
+    let rest = rest.trim();
+    let close = match rest.chars().next() {
+        Some('"') => '"',
+        Some('<') => '>',
+        _ => return None,
+    };
+    let rest = &rest[1..];
+    rest.find(close).map(|end| &rest[..end])
+}
+
 pub fn get_link_status(program_id: GLuint) -> GlResult<()> {
     let mut link_status = gl::FALSE as i32;
     unsafe {
@@ -75,6 +413,137 @@ pub fn get_link_status(program_id: GLuint) -> GlResult<()> {
     }
 }
 
+/// Create a single stage program flagged `GL_PROGRAM_SEPARABLE`, for use in a program pipeline.
+///
+/// The shader is freed after the program is linked.
+pub fn create_separable_program(kind: GLenum, source: &str) -> GlResult<GLuint> {
+    let shader = create_shader(kind, source)?;
+    link_shaders(&[shader], true, |program| {
+        unsafe {
+            gl::ProgramParameteri(program, gl::PROGRAM_SEPARABLE, gl::TRUE as GLint);
+        }
+    })
+}
+
+pub fn create_pipeline() -> GlResult<GLuint> {
+    unsafe {
+        let mut pipeline = 0;
+        gl::GenProgramPipelines(1, &mut pipeline);
+        if pipeline == 0 {
+            return Err(GlError::PipelineCreation);
+        }
+        Ok(pipeline)
+    }
+}
+
+/// Use the stages of the separable `program` selected by `stages` (e.g.
+/// `gl::VERTEX_SHADER_BIT`) in `pipeline`.
+pub fn use_program_stages(pipeline: GLuint, stages: GLbitfield, program: GLuint) {
+    unsafe {
+        gl::UseProgramStages(pipeline, stages, program);
+    }
+}
+
+/// Retrieve the driver-specific binary of a linked program along with its binary format.
+///
+/// Many drivers only keep a binary for programs linked with
+/// `GL_PROGRAM_BINARY_RETRIEVABLE_HINT` set, so create the program with
+/// `create_linked_program_retrievable`. Returns `GlError::ProgramLinkage` if the program has no
+/// binary (e.g. it is not linked, or the hint wasn't set).
+pub fn get_program_binary(program: GLuint) -> GlResult<(GLenum, Vec<u8>)> {
+    unsafe {
+        let mut length: GLint = 0;
+        gl::GetProgramiv(program, gl::PROGRAM_BINARY_LENGTH, &mut length);
+        if length <= 0 {
+            return Err(GlError::ProgramLinkage(Some("program has no binary".to_string())));
+        }
+        let mut binary = vec![0u8; length as usize];
+        let mut written: GLsizei = 0;
+        let mut format: GLenum = 0;
+        gl::GetProgramBinary(program, length, &mut written, &mut format,
+            binary.as_mut_ptr() as *mut _);
+        binary.truncate(written as usize);
+        Ok((format, binary))
+    }
+}
+
+/// Create an OpenGL program from a binary previously returned by `get_program_binary`.
+///
+/// Drivers may reject a cached binary (for example after a driver update), in which case the
+/// program is deleted and `GlError::ProgramLinkage` is returned so the caller can fall back to
+/// compiling from source.
+pub fn create_program_from_binary(format: GLenum, bytes: &[u8]) -> GlResult<GLuint> {
+    let program = create_program()?;
+    unsafe {
+        gl::ProgramBinary(program, format, bytes.as_ptr() as *const _, bytes.len() as GLsizei);
+    }
+    if let Err(e) = get_link_status(program) {
+        unsafe {
+            gl::DeleteProgram(program);
+        }
+        return Err(e);
+    }
+    Ok(program)
+}
+
+/// Check whether `program` can execute given the current GL state, via `glValidateProgram`.
+///
+/// Intended for use right before a draw call during development. Returns
+/// `GlError::ProgramValidation` with the info log if validation fails.
+pub fn validate_program(program: GLuint) -> GlResult<()> {
+    let mut status = gl::FALSE as i32;
+    unsafe {
+        gl::ValidateProgram(program);
+        gl::GetProgramiv(program, gl::VALIDATE_STATUS, &mut status);
+        if status != gl::TRUE as i32 {
+            Err(GlError::ProgramValidation(
+                get_info_log!(gl::GetProgramiv, gl::GetProgramInfoLog, program)
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Assign the uniform block `block_name` of `program` to the uniform buffer binding point
+/// `binding`.
+///
+/// Attach a buffer to the same binding point with `bind_buffer_base(gl::UNIFORM_BUFFER, ...)`.
+/// Returns `GlError::NotFound` if the program has no active block with that name.
+pub fn bind_uniform_block(program: GLuint, block_name: &str, binding: u32) -> GlResult<()> {
+    let c_name = CString::new(block_name)
+        .map_err(|_| GlError::NotFound(block_name.to_string()))?;
+    unsafe {
+        let index = gl::GetUniformBlockIndex(program, c_name.as_ptr());
+        if index == gl::INVALID_INDEX {
+            return Err(GlError::NotFound(block_name.to_string()));
+        }
+        gl::UniformBlockBinding(program, index, binding);
+    }
+    Ok(())
+}
+
+/// Assign the shader storage block `block_name` of `program` to the shader storage buffer
+/// binding point `binding`.
+///
+/// Attach a buffer with `bind_buffer_base(gl::SHADER_STORAGE_BUFFER, ...)`. Returns
+/// `GlError::NotFound` if the program has no active block with that name.
+pub fn bind_shader_storage_block(program: GLuint, block_name: &str, binding: u32)
+    -> GlResult<()>
+{
+    let c_name = CString::new(block_name)
+        .map_err(|_| GlError::NotFound(block_name.to_string()))?;
+    unsafe {
+        let index = gl::GetProgramResourceIndex(program, gl::SHADER_STORAGE_BLOCK,
+                                                c_name.as_ptr());
+        if index == gl::INVALID_INDEX {
+            return Err(GlError::NotFound(block_name.to_string()));
+        }
+        gl::ShaderStorageBlockBinding(program, index, binding);
+    }
+    Ok(())
+}
+
 /// Create an OpenGL program with one function call.
 ///
 /// Will report both shader compilation errors and program link errors.
@@ -84,26 +553,209 @@ pub fn get_link_status(program_id: GLuint) -> GlResult<()> {
 pub fn create_basic_program(vertex_source: &str, fragment_source: &str) -> GlResult<GLuint> {
     let vertex_shader = create_shader(gl::VERTEX_SHADER, vertex_source)?;
     let fragment_shader = create_shader(gl::FRAGMENT_SHADER, fragment_source)?;
-    Ok(create_linked_program(
+    create_linked_program(
         &[
             vertex_shader,
             fragment_shader,
         ],
         true
-    )?)
+    )
+}
+
+/// Like `create_basic_program`, but reads the shader sources from the given files.
+///
+/// Both files are read before any shader is created, so a `GlError::Io` never leaks a shader.
+pub fn create_basic_program_from_files(vertex_path: &Path, fragment_path: &Path)
+    -> GlResult<GLuint>
+{
+    let vertex_source = fs::read_to_string(vertex_path)?;
+    let fragment_source = fs::read_to_string(fragment_path)?;
+    create_basic_program(&vertex_source, &fragment_source)
+}
+
+/// Create an OpenGL program from a vertex, geometry, and fragment shader with one function call.
+///
+/// Behaves exactly like `create_basic_program`, with an additional geometry stage. Shaders will be
+/// created and then freed after the program is linked.
+pub fn create_program_vgf(vertex_source: &str, geometry_source: &str, fragment_source: &str)
+    -> GlResult<GLuint>
+{
+    let vertex_shader = create_shader(gl::VERTEX_SHADER, vertex_source)?;
+    let geometry_shader = create_shader(gl::GEOMETRY_SHADER, geometry_source)?;
+    let fragment_shader = create_shader(gl::FRAGMENT_SHADER, fragment_source)?;
+    create_linked_program(
+        &[
+            vertex_shader,
+            geometry_shader,
+            fragment_shader,
+        ],
+        true
+    )
 }
 
 /// Create an OpenGL program given a slice of shader references.
 ///
 /// Pass `true` for `delete_shaders` in order to automatically delete each shader after linking.
 pub fn create_linked_program(shaders: &[GLuint], delete_shaders: bool) -> GlResult<GLuint> {
+    link_shaders(shaders, delete_shaders, |_| {})
+}
+
+/// Like `create_linked_program`, but takes the stage of each shader as `(kind, id)` pairs and
+/// checks that the stages can form a program before calling GL.
+///
+/// A program needs either a vertex stage or a lone compute stage, and a tessellation control
+/// stage needs an evaluation stage. Otherwise `GlError::ProgramLinkage` is returned with a
+/// message such as `"missing vertex stage"`, and the shaders are left untouched.
+pub fn create_program_checked(shaders: &[(GLenum, GLuint)], delete_shaders: bool)
+    -> GlResult<GLuint>
+{
+    check_stages(shaders)?;
+    let ids: Vec<GLuint> = shaders.iter().map(|&(_, id)| id).collect();
+    link_shaders(&ids, delete_shaders, |_| {})
+}
+
+fn check_stages(shaders: &[(GLenum, GLuint)]) -> GlResult<()> {
+    let has = |kind| shaders.iter().any(|&(k, _)| k == kind);
+    let problem = if has(gl::COMPUTE_SHADER) {
+        if shaders.len() > 1 {
+            Some("compute stage cannot be combined with other stages")
+        } else {
+            None
+        }
+    } else if !has(gl::VERTEX_SHADER) {
+        Some("missing vertex stage")
+    } else if has(gl::TESS_CONTROL_SHADER) && !has(gl::TESS_EVALUATION_SHADER) {
+        Some("missing tessellation evaluation stage")
+    } else {
+        None
+    };
+    match problem {
+        Some(problem) => Err(GlError::ProgramLinkage(Some(problem.to_string()))),
+        None => Ok(()),
+    }
+}
+
+/// Like `create_linked_program`, but sets `GL_PROGRAM_BINARY_RETRIEVABLE_HINT` before linking
+/// so that `get_program_binary` can retrieve the result.
+pub fn create_linked_program_retrievable(shaders: &[GLuint], delete_shaders: bool)
+    -> GlResult<GLuint>
+{
+    link_shaders(shaders, delete_shaders, |program| {
+        unsafe {
+            gl::ProgramParameteri(program, gl::PROGRAM_BINARY_RETRIEVABLE_HINT, gl::TRUE as GLint);
+        }
+    })
+}
+
+/// Like `create_linked_program`, but binds each `(index, name)` attribute to its location before
+/// linking.
+///
+/// Use this to guarantee that a `buffer_layout!` declared at `index` lines up with the named
+/// `in` variable regardless of declaration order in the GLSL.
+pub fn create_linked_program_with_attribs(shaders: &[GLuint], bindings: &[(u32, &str)],
+                                          delete_shaders: bool) -> GlResult<GLuint> {
+    let bindings = to_c_bindings(bindings)?;
+    link_shaders(shaders, delete_shaders, |program| {
+        for &(index, ref name) in &bindings {
+            unsafe {
+                gl::BindAttribLocation(program, index, name.as_ptr());
+            }
+        }
+    })
+}
+
+/// Like `create_linked_program`, but binds each `(index, name)` fragment output to its color
+/// number before linking.
+///
+/// This deterministically maps e.g. `out vec4 gAlbedo` to `GL_COLOR_ATTACHMENT0` for multiple
+/// render targets.
+pub fn create_linked_program_with_frag_data(shaders: &[GLuint], frag_bindings: &[(u32, &str)],
+                                            delete_shaders: bool) -> GlResult<GLuint> {
+    let frag_bindings = to_c_bindings(frag_bindings)?;
+    link_shaders(shaders, delete_shaders, |program| {
+        for &(index, ref name) in &frag_bindings {
+            unsafe {
+                gl::BindFragDataLocation(program, index, name.as_ptr());
+            }
+        }
+    })
+}
+
+/// Like `create_linked_program`, but declares the outputs captured by transform feedback before
+/// linking.
+///
+/// Pass `interleaved` to capture every varying into one buffer (`GL_INTERLEAVED_ATTRIBS`) rather
+/// than one buffer per varying (`GL_SEPARATE_ATTRIBS`).
+pub fn create_linked_program_with_feedback(shaders: &[GLuint], varyings: &[&str],
+                                           interleaved: bool, delete_shaders: bool)
+    -> GlResult<GLuint>
+{
+    let varyings = varyings.iter()
+        .map(|&name| {
+            CString::new(name).map_err(|_| GlError::ProgramLinkage(
+                Some(format!("varying name {:?} contains a nul byte", name))
+            ))
+        })
+        .collect::<GlResult<Vec<_>>>()?;
+    let names: Vec<*const GLchar> = varyings.iter().map(|name| name.as_ptr()).collect();
+    let mode = if interleaved { gl::INTERLEAVED_ATTRIBS } else { gl::SEPARATE_ATTRIBS };
+    link_shaders(shaders, delete_shaders, |program| {
+        unsafe {
+            gl::TransformFeedbackVaryings(program, names.len() as GLsizei, names.as_ptr(), mode);
+        }
+    })
+}
+
+fn to_c_bindings(bindings: &[(u32, &str)]) -> GlResult<Vec<(u32, CString)>> {
+    bindings.iter()
+        .map(|&(index, name)| {
+            CString::new(name)
+                .map(|name| (index, name))
+                .map_err(|_| GlError::ProgramLinkage(
+                    Some(format!("binding name {:?} contains a nul byte", name))
+                ))
+        })
+        .collect()
+}
+
+/// Link `program`, whose shaders the caller has already attached, and check the result.
+///
+/// Unlike `create_linked_program` this doesn't attach, detach, or delete anything, leaving the
+/// shader lifecycle entirely to the caller. Returns `GlError::ProgramLinkage` with the info log
+/// if linking fails.
+pub fn link_program(program: GLuint) -> GlResult<()> {
+    unsafe {
+        gl::LinkProgram(program);
+    }
+    get_link_status(program)
+}
+
+/// Attach `shaders` to a new program and start linking it, without waiting for or checking the
+/// result.
+///
+/// Poll `is_program_linked`, then call `get_link_status`. The shaders remain attached.
+pub fn start_program_link(shaders: &[GLuint]) -> GlResult<GLuint> {
     let program = create_program()?;
     unsafe {
         for &shader in shaders {
             gl::AttachShader(program, shader);
         }
         gl::LinkProgram(program);
-        get_link_status(program)?;
+    }
+    Ok(program)
+}
+
+/// Attach `shaders` to a new program, call `before_link` with its id, then link.
+fn link_shaders<F>(shaders: &[GLuint], delete_shaders: bool, before_link: F) -> GlResult<GLuint>
+    where F: FnOnce(GLuint)
+{
+    let program = create_program()?;
+    unsafe {
+        for &shader in shaders {
+            gl::AttachShader(program, shader);
+        }
+        before_link(program);
+        link_program(program)?;
         // we have to detach the shaders before the shader objects will be freed
         for &shader in shaders {
             gl::DetachShader(program, shader);
@@ -114,3 +766,216 @@ pub fn create_linked_program(shaders: &[GLuint], delete_shaders: bool) -> GlResu
     }
     Ok(program)
 }
+
+/// Create an OpenGL program with vertex, tessellation control, tessellation evaluation, and
+/// fragment stages.
+///
+/// Shaders will be created and then freed after the program is linked.
+pub fn create_tessellated_program(vertex_source: &str, tess_control_source: &str,
+                                  tess_eval_source: &str, fragment_source: &str)
+    -> GlResult<GLuint>
+{
+    let vertex_shader = create_shader(gl::VERTEX_SHADER, vertex_source)?;
+    let tess_control_shader = create_shader(gl::TESS_CONTROL_SHADER, tess_control_source)?;
+    let tess_eval_shader = create_shader(gl::TESS_EVALUATION_SHADER, tess_eval_source)?;
+    let fragment_shader = create_shader(gl::FRAGMENT_SHADER, fragment_source)?;
+    create_linked_program(
+        &[
+            vertex_shader,
+            tess_control_shader,
+            tess_eval_shader,
+            fragment_shader,
+        ],
+        true
+    )
+}
+
+/// Create an OpenGL compute program from a single compute shader source.
+///
+/// The shader is freed after the program is linked.
+pub fn create_compute_program(source: &str) -> GlResult<GLuint> {
+    let compute_shader = create_shader(gl::COMPUTE_SHADER, source)?;
+    create_linked_program(&[compute_shader], true)
+}
+
+/// Launch the currently bound compute program with the given number of work groups.
+pub fn dispatch_compute(x: u32, y: u32, z: u32) {
+    unsafe {
+        gl::DispatchCompute(x, y, z);
+    }
+}
+
+/// Order memory transactions issued by previous shader invocations (i.e. `glMemoryBarrier`).
+///
+/// `bits` is a combination of barrier bits such as `gl::SHADER_STORAGE_BARRIER_BIT`.
+pub fn memory_barrier(bits: GLbitfield) {
+    unsafe {
+        gl::MemoryBarrier(bits);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_stages, create_program_vgf, create_shader, expand_includes,
+                get_program_binary, get_shader_source, is_shader_compiled, with_defines};
+    use error::{GlResult, GlError};
+
+    use gl;
+    use gl::types::*;
+
+    use std::ptr;
+
+    const COMPILE_LOG: &[u8] = b"0:1(1): error: syntax error\0";
+
+    extern "system" fn mock_create_shader(_: GLenum) -> GLuint {
+        1
+    }
+
+    extern "system" fn mock_shader_source(_: GLuint, _: GLsizei, _: *const *const GLchar,
+                                          _: *const GLint) {}
+
+    extern "system" fn mock_compile_shader(_: GLuint) {}
+
+    extern "system" fn mock_get_shader_iv(_: GLuint, pname: GLenum, params: *mut GLint) {
+        let value = match pname {
+            gl::COMPILE_STATUS => gl::FALSE as GLint,
+            gl::INFO_LOG_LENGTH | gl::SHADER_SOURCE_LENGTH => COMPILE_LOG.len() as GLint,
+            _ => 0,
+        };
+        unsafe {
+            *params = value;
+        }
+    }
+
+    /// Behaves like a conforming driver: the terminator is written, but not counted in `length`.
+    extern "system" fn mock_get_shader_info_log(_: GLuint, buf_size: GLsizei,
+                                                length: *mut GLsizei, log: *mut GLchar) {
+        let n = (buf_size as usize).min(COMPILE_LOG.len());
+        unsafe {
+            ptr::copy_nonoverlapping(COMPILE_LOG.as_ptr() as *const GLchar, log, n);
+            *log.add(n - 1) = 0;
+            if !length.is_null() {
+                *length = n as GLsizei - 1;
+            }
+        }
+    }
+
+    fn mock_failing_compile() {
+        gl::CreateShader::load_with(|_| mock_create_shader as *const _);
+        gl::ShaderSource::load_with(|_| mock_shader_source as *const _);
+        gl::CompileShader::load_with(|_| mock_compile_shader as *const _);
+        gl::GetShaderiv::load_with(|_| mock_get_shader_iv as *const _);
+        gl::GetShaderInfoLog::load_with(|_| mock_get_shader_info_log as *const _);
+        // source and log read back through the same length-then-copy protocol
+        gl::GetShaderSource::load_with(|_| mock_get_shader_info_log as *const _);
+    }
+
+    #[test]
+    fn completion_status_is_not_queried_without_the_extension() {
+        mock_failing_compile();
+        // the mock reports 0 for `GL_COMPLETION_STATUS_KHR`, as if the compile were pending
+        assert!(is_shader_compiled(1));
+    }
+
+    #[test]
+    fn compile_failure_log_has_no_trailing_nul() {
+        mock_failing_compile();
+        match create_shader(gl::VERTEX_SHADER, "void main() { oops }") {
+            Err(GlError::ShaderCompilation(Some(ref log))) => {
+                assert_eq!(log, "0:1(1): error: syntax error");
+            },
+            other => panic!("expected a compilation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn vgf_program_reports_compile_failures() {
+        mock_failing_compile();
+        match create_program_vgf("void main() {}", "void main() {}", "void main() {}") {
+            Err(GlError::ShaderCompilation(Some(_))) => {},
+            other => panic!("expected a compilation error, got {:?}", other),
+        }
+    }
+
+    extern "system" fn mock_get_program_iv(_: GLuint, _: GLenum, params: *mut GLint) {
+        unsafe {
+            *params = 0;
+        }
+    }
+
+    #[test]
+    fn missing_program_binary_is_not_a_gl_error() {
+        gl::GetProgramiv::load_with(|_| mock_get_program_iv as *const _);
+        match get_program_binary(1) {
+            Err(GlError::ProgramLinkage(Some(_))) => {},
+            other => panic!("expected a linkage error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn checks_program_stages() {
+        let missing = |problem: &str| Err(GlError::ProgramLinkage(Some(problem.to_string())));
+        assert_eq!(check_stages(&[(gl::VERTEX_SHADER, 1), (gl::FRAGMENT_SHADER, 2)]), Ok(()));
+        assert_eq!(check_stages(&[(gl::COMPUTE_SHADER, 1)]), Ok(()));
+        assert_eq!(check_stages(&[(gl::FRAGMENT_SHADER, 2)]), missing("missing vertex stage"));
+        assert_eq!(check_stages(&[(gl::VERTEX_SHADER, 1), (gl::TESS_CONTROL_SHADER, 2)]),
+                   missing("missing tessellation evaluation stage"));
+        assert_eq!(check_stages(&[(gl::VERTEX_SHADER, 1), (gl::COMPUTE_SHADER, 2)]),
+                   missing("compute stage cannot be combined with other stages"));
+    }
+
+    #[test]
+    fn shader_source_has_no_trailing_nul() {
+        mock_failing_compile();
+        assert_eq!(get_shader_source(1), Ok("0:1(1): error: syntax error".to_string()));
+    }
+
+    fn resolve(name: &str) -> GlResult<String> {
+        match name {
+            "common.glsl" => Ok("float common() { return 1.0; }".to_string()),
+            "lighting.glsl" => Ok("#include \"common.glsl\"\nvec3 light();".to_string()),
+            "a.glsl" => Ok("#include \"b.glsl\"".to_string()),
+            "b.glsl" => Ok("#include <a.glsl>".to_string()),
+            _ => Err(GlError::ShaderCompilation(Some(format!("unknown include {}", name)))),
+        }
+    }
+
+    #[test]
+    fn expands_nested_includes() {
+        let source = "#version 330\n  #include \"lighting.glsl\"\nvoid main() {}";
+        let expanded = expand_includes(source, &resolve, &mut Vec::new()).unwrap();
+        assert_eq!(expanded,
+            "#version 330\nfloat common() { return 1.0; }\nvec3 light();\nvoid main() {}\n");
+    }
+
+    #[test]
+    fn allows_including_the_same_file_twice() {
+        let source = "#include \"common.glsl\"\n#include \"common.glsl\"";
+        let expanded = expand_includes(source, &resolve, &mut Vec::new()).unwrap();
+        assert_eq!(expanded.matches("common()").count(), 2);
+    }
+
+    #[test]
+    fn detects_include_cycles() {
+        match expand_includes("#include \"a.glsl\"", &resolve, &mut Vec::new()) {
+            Err(GlError::IncludeCycle(ref name)) if name == "a.glsl" => {},
+            other => panic!("expected an include cycle, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn forwards_resolver_errors() {
+        match expand_includes("#include \"missing.glsl\"", &resolve, &mut Vec::new()) {
+            Err(GlError::ShaderCompilation(Some(_))) => {},
+            other => panic!("expected the resolver error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn prefixes_version_and_defines() {
+        let source = with_defines("330 core", &[("MAX_LIGHTS", "4"), ("USE_FOG", "")],
+                                  "void main() {}");
+        assert_eq!(source, "#version 330 core\n#define MAX_LIGHTS 4\n#define USE_FOG \n\
+                            #line 1\nvoid main() {}");
+    }
+}
@@ -0,0 +1,45 @@
+//! Thin wrappers over fence sync objects.
+
+// `GLsync` is an opaque handle that GL validates, not a pointer we dereference
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use gl;
+use gl::types::*;
+
+use error::{GlResult, GlError};
+
+/// Insert a fence that is signaled once every command issued before it has completed.
+pub fn fence_sync() -> GLsync {
+    unsafe {
+        gl::FenceSync(gl::SYNC_GPU_COMMANDS_COMPLETE, 0)
+    }
+}
+
+/// Block for up to `timeout_ns` nanoseconds for `sync` to be signaled, flushing first so the
+/// fence is guaranteed to eventually be reached.
+///
+/// Returns `gl::ALREADY_SIGNALED`, `gl::CONDITION_SATISFIED`, `gl::TIMEOUT_EXPIRED`, or
+/// `gl::WAIT_FAILED`.
+pub fn client_wait_sync(sync: GLsync, timeout_ns: u64) -> GLenum {
+    unsafe {
+        gl::ClientWaitSync(sync, gl::SYNC_FLUSH_COMMANDS_BIT, timeout_ns)
+    }
+}
+
+pub fn delete_sync(sync: GLsync) {
+    unsafe {
+        gl::DeleteSync(sync);
+    }
+}
+
+/// `client_wait_sync`, reporting anything but the fence being signaled as an error.
+///
+/// Returns `GlError::SyncTimeout` if `timeout_ns` elapsed and `GlError::SyncWaitFailed` if GL
+/// could not wait at all (e.g. `sync` is not a sync object).
+pub fn wait_for_sync(sync: GLsync, timeout_ns: u64) -> GlResult<()> {
+    match client_wait_sync(sync, timeout_ns) {
+        gl::ALREADY_SIGNALED | gl::CONDITION_SATISFIED => Ok(()),
+        gl::TIMEOUT_EXPIRED => Err(GlError::SyncTimeout),
+        _ => Err(GlError::SyncWaitFailed),
+    }
+}
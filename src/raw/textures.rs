@@ -0,0 +1,550 @@
+//! Thin wrappers over texture objects. Like the buffer helpers, these leave the texture bound.
+
+use gl;
+use gl::types::*;
+
+use error::{GlResult, GlError};
+
+use std::ptr;
+
+pub fn create_texture() -> GlResult<GLuint> {
+    unsafe {
+        let mut tex = 0;
+        gl::GenTextures(1, &mut tex);
+        if tex == 0 {
+            return Err(GlError::TextureCreation(None));
+        }
+        Ok(tex)
+    }
+}
+
+pub fn delete_texture(tex: GLuint) {
+    unsafe {
+        gl::DeleteTextures(1, &tex);
+    }
+}
+
+/// Bind `tex` to `gl::TEXTURE_2D` and specify its base level with `glTexImage2D`.
+///
+/// `format` and `ty` describe `data`; `internal_format` is how GL stores it. An empty `data`
+/// allocates the level without initializing it, e.g. for a framebuffer attachment.
+///
+/// Returns `GlError::TextureCreation` if `data` is non-empty but shorter than GL will read for
+/// the image under the current `gl::UNPACK_*` pixel store state.
+pub fn upload_texture_2d(tex: GLuint, internal_format: GLenum, width: u32, height: u32,
+                         format: GLenum, ty: GLenum, data: &[u8]) -> GlResult<()>
+{
+    let pixels = if data.is_empty() {
+        ptr::null()
+    } else {
+        checked_pixels(width, height, 1, false, format, ty, data)?
+    };
+    unsafe {
+        gl::BindTexture(gl::TEXTURE_2D, tex);
+        gl::TexImage2D(
+            gl::TEXTURE_2D,
+            0,
+            internal_format as GLint,
+            width as GLsizei,
+            height as GLsizei,
+            0,
+            format,
+            ty,
+            pixels
+        );
+    }
+    Ok(())
+}
+
+/// The `gl::UNPACK_*` pixel store state that decides which bytes GL reads for an upload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct UnpackState {
+    pub alignment: u32,
+    pub row_length: u32,
+    pub skip_pixels: u32,
+    pub skip_rows: u32,
+    /// Only used by 3D uploads, like `image_height`.
+    pub skip_images: u32,
+    pub image_height: u32,
+}
+
+impl Default for UnpackState {
+    fn default() -> UnpackState {
+        UnpackState {
+            alignment: 4,
+            row_length: 0,
+            skip_pixels: 0,
+            skip_rows: 0,
+            skip_images: 0,
+            image_height: 0,
+        }
+    }
+}
+
+impl UnpackState {
+    /// Query the current state. The image parameters are left at 0 unless `three_d` is set,
+    /// since GL ignores them for 2D uploads.
+    fn current(three_d: bool) -> UnpackState {
+        let get = |pname| {
+            let mut value = 0;
+            unsafe {
+                gl::GetIntegerv(pname, &mut value);
+            }
+            value.max(0) as u32
+        };
+        UnpackState {
+            alignment: get(gl::UNPACK_ALIGNMENT),
+            row_length: get(gl::UNPACK_ROW_LENGTH),
+            skip_pixels: get(gl::UNPACK_SKIP_PIXELS),
+            skip_rows: get(gl::UNPACK_SKIP_ROWS),
+            skip_images: if three_d { get(gl::UNPACK_SKIP_IMAGES) } else { 0 },
+            image_height: if three_d { get(gl::UNPACK_IMAGE_HEIGHT) } else { 0 },
+        }
+    }
+}
+
+/// The number of bytes from the start of the data that GL reads for a `width` x `height` x
+/// `depth` image of `format` and `ty` under `unpack`.
+///
+/// The last row is not padded. Returns `None` for formats and types this doesn't know.
+pub(crate) fn pixel_data_len(width: u32, height: u32, depth: u32, format: GLenum, ty: GLenum,
+                             unpack: &UnpackState) -> Option<usize>
+{
+    let components = match format {
+        gl::RED | gl::GREEN | gl::BLUE | gl::RED_INTEGER | gl::GREEN_INTEGER |
+        gl::BLUE_INTEGER | gl::DEPTH_COMPONENT | gl::STENCIL_INDEX => 1,
+        gl::RG | gl::RG_INTEGER | gl::DEPTH_STENCIL => 2,
+        gl::RGB | gl::BGR | gl::RGB_INTEGER | gl::BGR_INTEGER => 3,
+        gl::RGBA | gl::BGRA | gl::RGBA_INTEGER | gl::BGRA_INTEGER => 4,
+        _ => return None,
+    };
+    let pixel_size = match ty {
+        gl::UNSIGNED_BYTE | gl::BYTE => components,
+        gl::UNSIGNED_SHORT | gl::SHORT | gl::HALF_FLOAT => components * 2,
+        gl::UNSIGNED_INT | gl::INT | gl::FLOAT => components * 4,
+        // packed types hold a whole pixel
+        gl::UNSIGNED_BYTE_3_3_2 | gl::UNSIGNED_BYTE_2_3_3_REV => 1,
+        gl::UNSIGNED_SHORT_5_6_5 | gl::UNSIGNED_SHORT_5_6_5_REV | gl::UNSIGNED_SHORT_4_4_4_4 |
+        gl::UNSIGNED_SHORT_4_4_4_4_REV | gl::UNSIGNED_SHORT_5_5_5_1 |
+        gl::UNSIGNED_SHORT_1_5_5_5_REV => 2,
+        gl::UNSIGNED_INT_8_8_8_8 | gl::UNSIGNED_INT_8_8_8_8_REV | gl::UNSIGNED_INT_10_10_10_2 |
+        gl::UNSIGNED_INT_2_10_10_10_REV | gl::UNSIGNED_INT_24_8 |
+        gl::UNSIGNED_INT_10F_11F_11F_REV | gl::UNSIGNED_INT_5_9_9_9_REV => 4,
+        gl::FLOAT_32_UNSIGNED_INT_24_8_REV => 8,
+        _ => return None,
+    };
+    if width == 0 || height == 0 || depth == 0 {
+        return Some(0);
+    }
+    let pick = |set: u32, default: u32| (if set > 0 { set } else { default }) as usize;
+    let alignment = unpack.alignment.max(1) as usize;
+    // rows are `row_length` pixels apart and images `image_height` rows apart, if set
+    let row_stride = (pick(unpack.row_length, width) * pixel_size).div_ceil(alignment) * alignment;
+    let image_stride = row_stride * pick(unpack.image_height, height);
+    let skipped = unpack.skip_images as usize * image_stride +
+        unpack.skip_rows as usize * row_stride + unpack.skip_pixels as usize * pixel_size;
+    Some(skipped + (depth as usize - 1) * image_stride + (height as usize - 1) * row_stride +
+         width as usize * pixel_size)
+}
+
+/// A pointer to `data` for an upload of the given image, or `GlError::TextureCreation` if `data`
+/// is shorter than GL will read under the current `gl::UNPACK_*` state.
+fn checked_pixels(width: u32, height: u32, depth: u32, three_d: bool, format: GLenum, ty: GLenum,
+                  data: &[u8]) -> GlResult<*const GLvoid>
+{
+    let unpack = UnpackState::current(three_d);
+    let expected = pixel_data_len(width, height, depth, format, ty, &unpack)
+        .ok_or_else(|| GlError::TextureCreation(Some(format!(
+            "unknown pixel format {:#x} or type {:#x}", format, ty
+        ))))?;
+    if data.len() < expected {
+        return Err(GlError::TextureCreation(Some(format!(
+            "{}x{}x{} image of format {:#x} and type {:#x} needs {} bytes, got {}",
+            width, height, depth, format, ty, expected, data.len()
+        ))));
+    }
+    // GL won't read from the pointer, but a dangling one is still invalid to pass
+    Ok(if expected == 0 { ptr::null() } else { data.as_ptr() as *const _ })
+}
+
+/// Generate mipmaps for the texture bound to `target` from its base level.
+///
+/// Mipmaps are only sampled if the min filter is a mipmapped mode such as
+/// `gl::LINEAR_MIPMAP_LINEAR`; setting that is left to the caller.
+pub fn generate_mipmaps(target: GLenum) {
+    unsafe {
+        gl::GenerateMipmap(target);
+    }
+}
+
+/// `upload_texture_2d` followed by `generate_mipmaps`.
+///
+/// As with `generate_mipmaps`, the caller must select a mipmapped min filter.
+pub fn upload_texture_2d_mipmapped(tex: GLuint, internal_format: GLenum, width: u32, height: u32,
+                                   format: GLenum, ty: GLenum, data: &[u8]) -> GlResult<()>
+{
+    upload_texture_2d(tex, internal_format, width, height, format, ty, data)?;
+    generate_mipmaps(gl::TEXTURE_2D);
+    Ok(())
+}
+
+/// Create a `gl::TEXTURE_2D` with immutable storage for `levels` mip levels, via
+/// `glTexStorage2D`. This is the preferred way to allocate textures; fill it in with
+/// `upload_texture_subimage_2d`.
+///
+/// The texture is left bound. Returns `GlError::TextureCreation` if GL rejected the storage
+/// (e.g. an unsized `internal_format` or too many levels).
+pub fn create_texture_storage_2d(internal_format: GLenum, width: u32, height: u32, levels: u32)
+    -> GlResult<GLuint>
+{
+    let tex = create_texture()?;
+    unsafe {
+        gl::BindTexture(gl::TEXTURE_2D, tex);
+        gl::TexStorage2D(gl::TEXTURE_2D, levels as GLsizei, internal_format,
+                         width as GLsizei, height as GLsizei);
+    }
+    check_immutable_storage(gl::TEXTURE_2D, tex, || format!(
+        "GL_TEXTURE_2D storage rejected: internal format {:#x}, {}x{}, {} levels",
+        internal_format, width, height, levels
+    ))?;
+    Ok(tex)
+}
+
+/// Delete `tex`, bound to `target`, unless its `glTexStorage*` allocation succeeded.
+fn check_immutable_storage<F>(target: GLenum, tex: GLuint, describe: F) -> GlResult<()>
+    where F: FnOnce() -> String
+{
+    let mut immutable = gl::FALSE as GLint;
+    unsafe {
+        // the format only becomes immutable if the allocation succeeded
+        gl::GetTexParameteriv(target, gl::TEXTURE_IMMUTABLE_FORMAT, &mut immutable);
+    }
+    if immutable == gl::FALSE as GLint {
+        delete_texture(tex);
+        return Err(GlError::TextureCreation(Some(describe())));
+    }
+    Ok(())
+}
+
+/// Bind `tex` to `gl::TEXTURE_2D` and replace the `width` x `height` region at `(x, y)` of
+/// `level` with `data`, via `glTexSubImage2D`.
+///
+/// Returns `GlError::TextureCreation` if `data` is too short for the region.
+#[allow(clippy::too_many_arguments)]
+pub fn upload_texture_subimage_2d(tex: GLuint, level: u32, x: u32, y: u32, width: u32,
+                                  height: u32, format: GLenum, ty: GLenum, data: &[u8])
+    -> GlResult<()>
+{
+    let pixels = checked_pixels(width, height, 1, false, format, ty, data)?;
+    unsafe {
+        gl::BindTexture(gl::TEXTURE_2D, tex);
+        gl::TexSubImage2D(
+            gl::TEXTURE_2D,
+            level as GLint,
+            x as GLint,
+            y as GLint,
+            width as GLsizei,
+            height as GLsizei,
+            format,
+            ty,
+            pixels
+        );
+    }
+    Ok(())
+}
+
+/// Set the filtering and wrap modes of the texture bound to `target`.
+pub fn set_texture_parameters(target: GLenum, min_filter: GLenum, mag_filter: GLenum,
+                              wrap_s: GLenum, wrap_t: GLenum)
+{
+    unsafe {
+        gl::TexParameteri(target, gl::TEXTURE_MIN_FILTER, min_filter as GLint);
+        gl::TexParameteri(target, gl::TEXTURE_MAG_FILTER, mag_filter as GLint);
+        gl::TexParameteri(target, gl::TEXTURE_WRAP_S, wrap_s as GLint);
+        gl::TexParameteri(target, gl::TEXTURE_WRAP_T, wrap_t as GLint);
+    }
+}
+
+/// The arguments of `set_texture_parameters`, defaulting to linear filtering and repeating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureParams {
+    pub min_filter: GLenum,
+    pub mag_filter: GLenum,
+    pub wrap_s: GLenum,
+    pub wrap_t: GLenum,
+}
+
+impl Default for TextureParams {
+    fn default() -> TextureParams {
+        TextureParams {
+            min_filter: gl::LINEAR,
+            mag_filter: gl::LINEAR,
+            wrap_s: gl::REPEAT,
+            wrap_t: gl::REPEAT,
+        }
+    }
+}
+
+impl TextureParams {
+    /// Apply these parameters to the texture bound to `target`.
+    pub fn apply(&self, target: GLenum) {
+        set_texture_parameters(target, self.min_filter, self.mag_filter, self.wrap_s,
+                               self.wrap_t);
+    }
+}
+
+/// Create a texture and bind it to `gl::TEXTURE_CUBE_MAP`, which fixes its target.
+pub fn create_cubemap() -> GlResult<GLuint> {
+    let tex = create_texture()?;
+    unsafe {
+        gl::BindTexture(gl::TEXTURE_CUBE_MAP, tex);
+    }
+    Ok(tex)
+}
+
+/// Bind the cubemap `tex` and specify the base level of one `face`, which is one of
+/// `gl::TEXTURE_CUBE_MAP_POSITIVE_X` through `gl::TEXTURE_CUBE_MAP_NEGATIVE_Z`.
+///
+/// As with `upload_texture_2d`, an empty `data` allocates the face without initializing it, and
+/// `GlError::TextureCreation` is returned if a non-empty `data` is too short.
+#[allow(clippy::too_many_arguments)]
+pub fn upload_cubemap_face(tex: GLuint, face: GLenum, internal_format: GLenum, width: u32,
+                           height: u32, format: GLenum, ty: GLenum, data: &[u8])
+    -> GlResult<()>
+{
+    let pixels = if data.is_empty() {
+        ptr::null()
+    } else {
+        checked_pixels(width, height, 1, false, format, ty, data)?
+    };
+    unsafe {
+        gl::BindTexture(gl::TEXTURE_CUBE_MAP, tex);
+        gl::TexImage2D(
+            face,
+            0,
+            internal_format as GLint,
+            width as GLsizei,
+            height as GLsizei,
+            0,
+            format,
+            ty,
+            pixels
+        );
+    }
+    Ok(())
+}
+
+/// Upload all six faces of the cubemap `tex`, in the order +X, -X, +Y, -Y, +Z, -Z.
+///
+/// Stops at the first face whose data is too short.
+pub fn upload_cubemap(tex: GLuint, faces: [&[u8]; 6], internal_format: GLenum, width: u32,
+                      height: u32, format: GLenum, ty: GLenum) -> GlResult<()>
+{
+    // the face enums are consecutive in exactly this order
+    for (i, data) in faces.iter().enumerate() {
+        upload_cubemap_face(tex, gl::TEXTURE_CUBE_MAP_POSITIVE_X + i as GLenum, internal_format,
+                            width, height, format, ty, data)?;
+    }
+    Ok(())
+}
+
+/// Create a `gl::TEXTURE_2D_MULTISAMPLE` with `samples` samples per pixel, for use as an MSAA
+/// framebuffer attachment. Resolve it with `blit_framebuffer`.
+///
+/// `fixed_locations` requests identical sample locations for every pixel, which is required to
+/// mix it with renderbuffer attachments. The texture is left bound. Returns
+/// `GlError::TextureCreation` if GL rejected the allocation (e.g. too many samples).
+pub fn create_texture_multisample_2d(samples: u32, internal_format: GLenum, width: u32,
+                                     height: u32, fixed_locations: bool) -> GlResult<GLuint>
+{
+    let tex = create_texture()?;
+    let mut allocated_width = 0;
+    unsafe {
+        gl::BindTexture(gl::TEXTURE_2D_MULTISAMPLE, tex);
+        gl::TexImage2DMultisample(gl::TEXTURE_2D_MULTISAMPLE, samples as GLsizei, internal_format,
+                                  width as GLsizei, height as GLsizei,
+                                  fixed_locations as GLboolean);
+        // a rejected allocation leaves the image empty
+        gl::GetTexLevelParameteriv(gl::TEXTURE_2D_MULTISAMPLE, 0, gl::TEXTURE_WIDTH,
+                                   &mut allocated_width);
+    }
+    if allocated_width == 0 {
+        delete_texture(tex);
+        return Err(GlError::TextureCreation(Some(format!(
+            "GL_TEXTURE_2D_MULTISAMPLE storage rejected: internal format {:#x}, {}x{}, {} samples",
+            internal_format, width, height, samples
+        ))));
+    }
+    Ok(tex)
+}
+
+/// Create a `gl::TEXTURE_2D_ARRAY` of `layers` images with immutable storage for `levels` mip
+/// levels, via `glTexStorage3D`. Fill it one layer at a time with
+/// `upload_texture_2d_array_layer`.
+///
+/// The texture is left bound. Returns `GlError::TextureCreation` if GL rejected the storage.
+pub fn create_texture_storage_2d_array(internal_format: GLenum, width: u32, height: u32,
+                                       layers: u32, levels: u32) -> GlResult<GLuint>
+{
+    let tex = create_texture()?;
+    unsafe {
+        gl::BindTexture(gl::TEXTURE_2D_ARRAY, tex);
+        gl::TexStorage3D(gl::TEXTURE_2D_ARRAY, levels as GLsizei, internal_format,
+                         width as GLsizei, height as GLsizei, layers as GLsizei);
+    }
+    check_immutable_storage(gl::TEXTURE_2D_ARRAY, tex, || format!(
+        "GL_TEXTURE_2D_ARRAY storage rejected: internal format {:#x}, {}x{}x{}, {} levels",
+        internal_format, width, height, layers, levels
+    ))?;
+    Ok(tex)
+}
+
+/// Bind `tex` to `gl::TEXTURE_2D_ARRAY` and replace the `width` x `height` image at the origin of
+/// `layer` of `level` with `data`, via `glTexSubImage3D`.
+///
+/// Returns `GlError::TextureCreation` if `data` is too short for one layer.
+#[allow(clippy::too_many_arguments)]
+pub fn upload_texture_2d_array_layer(tex: GLuint, layer: u32, level: u32, width: u32,
+                                     height: u32, format: GLenum, ty: GLenum, data: &[u8])
+    -> GlResult<()>
+{
+    let pixels = checked_pixels(width, height, 1, true, format, ty, data)?;
+    unsafe {
+        gl::BindTexture(gl::TEXTURE_2D_ARRAY, tex);
+        gl::TexSubImage3D(
+            gl::TEXTURE_2D_ARRAY,
+            level as GLint,
+            0,
+            0,
+            layer as GLint,
+            width as GLsizei,
+            height as GLsizei,
+            1,
+            format,
+            ty,
+            pixels
+        );
+    }
+    Ok(())
+}
+
+/// Create a `gl::DEPTH_COMPONENT24` texture with immutable storage, e.g. for a shadow map.
+///
+/// The texture clamps to the edge and has no mipmaps. With `comparison`, sampling it through a
+/// `sampler2DShadow` compares against the reference depth (`gl::LEQUAL`) and linear filtering
+/// gives hardware PCF; otherwise it samples raw depth with nearest filtering. The texture is
+/// left bound.
+pub fn create_depth_texture_2d(width: u32, height: u32, comparison: bool) -> GlResult<GLuint> {
+    let tex = create_texture_storage_2d(gl::DEPTH_COMPONENT24, width, height, 1)?;
+    let filter = if comparison { gl::LINEAR } else { gl::NEAREST };
+    set_texture_parameters(gl::TEXTURE_2D, filter, filter, gl::CLAMP_TO_EDGE,
+                           gl::CLAMP_TO_EDGE);
+    if comparison {
+        unsafe {
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_COMPARE_MODE,
+                              gl::COMPARE_REF_TO_TEXTURE as GLint);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_COMPARE_FUNC, gl::LEQUAL as GLint);
+        }
+    }
+    Ok(tex)
+}
+
+/// Run `f` with `gl::UNPACK_ALIGNMENT` set to `alignment`, then restore the previous value.
+///
+/// GL assumes each row of uploaded pixel data starts on a 4 byte boundary by default, which
+/// garbles tightly packed data such as RGB8 images with an odd width. Wrap those uploads in
+/// `with_unpack_alignment(1, || ...)`.
+pub fn with_unpack_alignment<F, R>(alignment: u32, f: F) -> R
+    where F: FnOnce() -> R
+{
+    let _alignment = UnpackAlignment::new(alignment);
+    f()
+}
+
+/// Sets `gl::UNPACK_ALIGNMENT` for as long as the guard lives, so that the previous value is
+/// restored even if the upload panics.
+struct UnpackAlignment {
+    previous: GLint,
+}
+
+impl UnpackAlignment {
+    fn new(alignment: u32) -> UnpackAlignment {
+        let mut previous = 0;
+        unsafe {
+            gl::GetIntegerv(gl::UNPACK_ALIGNMENT, &mut previous);
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, alignment as GLint);
+        }
+        UnpackAlignment { previous }
+    }
+}
+
+impl Drop for UnpackAlignment {
+    fn drop(&mut self) {
+        unsafe {
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, self.previous);
+        }
+    }
+}
+
+/// Bind `tex` to `gl::TEXTURE_2D` and specify its base level from pre-compressed blocks, e.g.
+/// `gl::COMPRESSED_RGBA_S3TC_DXT5_EXT` data, via `glCompressedTexImage2D`.
+///
+/// `data` must be exactly the size GL expects for `internal_format` at this size; nothing is
+/// computed about block sizes here. Returns `GlError::TextureCreation` if GL rejected the data.
+pub fn upload_compressed_texture_2d(tex: GLuint, internal_format: GLenum, width: u32,
+                                    height: u32, data: &[u8]) -> GlResult<()>
+{
+    let mut compressed = gl::FALSE as GLint;
+    unsafe {
+        gl::BindTexture(gl::TEXTURE_2D, tex);
+        gl::CompressedTexImage2D(gl::TEXTURE_2D, 0, internal_format, width as GLsizei,
+                                 height as GLsizei, 0, data.len() as GLsizei,
+                                 data.as_ptr() as *const _);
+        // a rejected upload leaves the level unspecified, which reads as uncompressed
+        gl::GetTexLevelParameteriv(gl::TEXTURE_2D, 0, gl::TEXTURE_COMPRESSED, &mut compressed);
+    }
+    if compressed == gl::FALSE as GLint {
+        return Err(GlError::TextureCreation(Some(format!(
+            "compressed GL_TEXTURE_2D upload rejected: internal format {:#x}, {}x{}, {} bytes",
+            internal_format, width, height, data.len()
+        ))));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{UnpackState, pixel_data_len};
+
+    use gl;
+
+    #[test]
+    fn pixel_data_len_pads_all_but_the_last_row() {
+        let packed = UnpackState { alignment: 1, ..UnpackState::default() };
+        // 3 byte rows padded to 4
+        assert_eq!(pixel_data_len(1, 3, 1, gl::RGB, gl::UNSIGNED_BYTE, &UnpackState::default()),
+                   Some(11));
+        assert_eq!(pixel_data_len(1, 3, 1, gl::RGB, gl::UNSIGNED_BYTE, &packed), Some(9));
+        assert_eq!(pixel_data_len(2, 2, 3, gl::RGBA, gl::FLOAT, &UnpackState::default()),
+                   Some(192));
+        assert_eq!(pixel_data_len(0, 4, 1, gl::RED, gl::UNSIGNED_BYTE, &packed), Some(0));
+        assert_eq!(pixel_data_len(1, 1, 1, gl::RGBA, gl::INT_2_10_10_10_REV, &packed), None);
+    }
+
+    #[test]
+    fn pixel_data_len_follows_the_unpack_state() {
+        // a 2x2 RGBA8 window into a 10x8 image, starting at (3, 1)
+        let window = UnpackState {
+            row_length: 10,
+            skip_pixels: 3,
+            skip_rows: 1,
+            ..UnpackState::default()
+        };
+        assert_eq!(pixel_data_len(2, 2, 1, gl::RGBA, gl::UNSIGNED_BYTE, &window),
+                   Some(40 + 12 + 40 + 8));
+        // the second layer of a volume whose images are 8 rows tall
+        let layer = UnpackState { skip_images: 1, image_height: 8, ..window };
+        assert_eq!(pixel_data_len(2, 2, 1, gl::RGBA, gl::UNSIGNED_BYTE, &layer),
+                   Some(320 + 40 + 12 + 40 + 8));
+    }
+}
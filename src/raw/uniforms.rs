@@ -0,0 +1,162 @@
+//! Thin wrappers over `glUniform*`. The target program is assumed to already be bound.
+
+use gl;
+use gl::types::*;
+
+use error::{GlResult, GlError};
+
+use std::collections::HashMap;
+use std::ffi::CString;
+
+/// Memoizes `glGetUniformLocation` lookups for a single program.
+pub struct UniformCache {
+    program: GLuint,
+    map: HashMap<String, GLint>,
+}
+
+impl UniformCache {
+    pub fn new(program: GLuint) -> UniformCache {
+        UniformCache {
+            program,
+            map: HashMap::new(),
+        }
+    }
+
+    pub fn program(&self) -> GLuint {
+        self.program
+    }
+
+    /// Look up the location of `name`, querying GL only the first time it is requested.
+    ///
+    /// Unknown uniforms (and names containing a nul byte) return GL's own `-1` sentinel, which
+    /// the `glUniform*` functions silently ignore.
+    pub fn location(&mut self, name: &str) -> GLint {
+        if let Some(&location) = self.map.get(name) {
+            return location;
+        }
+        let location = match CString::new(name) {
+            Ok(c_name) => unsafe { gl::GetUniformLocation(self.program, c_name.as_ptr()) },
+            Err(_) => -1,
+        };
+        self.map.insert(name.to_string(), location);
+        location
+    }
+}
+
+pub fn set_uniform_f32(location: GLint, v: f32) {
+    unsafe {
+        gl::Uniform1f(location, v);
+    }
+}
+
+pub fn set_uniform_vec2(location: GLint, v: [f32; 2]) {
+    unsafe {
+        gl::Uniform2f(location, v[0], v[1]);
+    }
+}
+
+pub fn set_uniform_vec3(location: GLint, v: [f32; 3]) {
+    unsafe {
+        gl::Uniform3f(location, v[0], v[1], v[2]);
+    }
+}
+
+pub fn set_uniform_vec4(location: GLint, v: [f32; 4]) {
+    unsafe {
+        gl::Uniform4f(location, v[0], v[1], v[2], v[3]);
+    }
+}
+
+/// Also used to assign texture units to samplers.
+pub fn set_uniform_i32(location: GLint, v: i32) {
+    unsafe {
+        gl::Uniform1i(location, v);
+    }
+}
+
+pub fn set_uniform_ivec2(location: GLint, v: [i32; 2]) {
+    unsafe {
+        gl::Uniform2i(location, v[0], v[1]);
+    }
+}
+
+pub fn set_uniform_ivec3(location: GLint, v: [i32; 3]) {
+    unsafe {
+        gl::Uniform3i(location, v[0], v[1], v[2]);
+    }
+}
+
+pub fn set_uniform_ivec4(location: GLint, v: [i32; 4]) {
+    unsafe {
+        gl::Uniform4i(location, v[0], v[1], v[2], v[3]);
+    }
+}
+
+pub fn set_uniform_u32(location: GLint, v: u32) {
+    unsafe {
+        gl::Uniform1ui(location, v);
+    }
+}
+
+pub fn set_uniform_uvec2(location: GLint, v: [u32; 2]) {
+    unsafe {
+        gl::Uniform2ui(location, v[0], v[1]);
+    }
+}
+
+pub fn set_uniform_uvec3(location: GLint, v: [u32; 3]) {
+    unsafe {
+        gl::Uniform3ui(location, v[0], v[1], v[2]);
+    }
+}
+
+pub fn set_uniform_uvec4(location: GLint, v: [u32; 4]) {
+    unsafe {
+        gl::Uniform4ui(location, v[0], v[1], v[2], v[3]);
+    }
+}
+
+/// Matrices are column major unless `transpose` is true.
+pub fn set_uniform_mat2(location: GLint, m: &[f32; 4], transpose: bool) {
+    unsafe {
+        gl::UniformMatrix2fv(location, 1, transpose as GLboolean, m.as_ptr());
+    }
+}
+
+/// Matrices are column major unless `transpose` is true.
+pub fn set_uniform_mat3(location: GLint, m: &[f32; 9], transpose: bool) {
+    unsafe {
+        gl::UniformMatrix3fv(location, 1, transpose as GLboolean, m.as_ptr());
+    }
+}
+
+/// Matrices are column major unless `transpose` is true.
+pub fn set_uniform_mat4(location: GLint, m: &[f32; 16], transpose: bool) {
+    unsafe {
+        gl::UniformMatrix4fv(location, 1, transpose as GLboolean, m.as_ptr());
+    }
+}
+
+/// Look up the index of the subroutine `name` in the `stage` (e.g. `gl::FRAGMENT_SHADER`) of
+/// `program`, for use with `set_subroutines`.
+///
+/// Returns `GlError::NotFound` if the stage has no active subroutine with that name.
+pub fn get_subroutine_index(program: GLuint, stage: GLenum, name: &str) -> GlResult<GLuint> {
+    let c_name = CString::new(name).map_err(|_| GlError::NotFound(name.to_string()))?;
+    let index = unsafe { gl::GetSubroutineIndex(program, stage, c_name.as_ptr()) };
+    if index == gl::INVALID_INDEX {
+        return Err(GlError::NotFound(name.to_string()));
+    }
+    Ok(index)
+}
+
+/// Select the subroutine for every subroutine uniform of `stage` in the bound program, where
+/// `indices[location]` is the subroutine index for the uniform at that location.
+///
+/// GL requires a value for every active subroutine uniform location of the stage, and forgets
+/// the selection whenever the program is used again, so call this after each `glUseProgram`.
+pub fn set_subroutines(stage: GLenum, indices: &[GLuint]) {
+    unsafe {
+        gl::UniformSubroutinesuiv(stage, indices.len() as GLsizei, indices.as_ptr());
+    }
+}